@@ -1,110 +1,67 @@
 // Simple example of an AI agent interacting with the MCP server
 
+use orbit_mcp::api::components::{ComponentEvent, ComponentProp, CreateComponentRequest};
+use orbit_mcp::client::McpClient;
+use orbit_mcp::utils::auth;
 use serde_json::json;
 use std::error::Error;
-use tokio::time::{sleep, Duration};
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
-use url::Url;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
     // Connect to the MCP server
-    let url = Url::parse("ws://localhost:3000/api/ws")?;
+    let url = "ws://localhost:3000/api/ws";
     println!("Connecting to MCP server at {}", url);
 
-    let (mut ws_stream, _) = connect_async(url).await?;
+    // The server wrote the token clients must present when it started
+    let token = auth::read_token_file(&auth::default_token_path(3000))?;
+    let client = McpClient::connect_websocket_with_token(url, &token).await?;
     println!("Connected to MCP server!");
 
-    // Wait for the welcome message
-    if let Some(Ok(message)) = ws_stream.next().await {
-        if let Message::Text(text) = message {
-            println!("Received welcome message: {}", text);
-        }
+    // List all components
+    let components = client.list_components().await?;
+    println!("Found {} components:", components.len());
+    for component in &components {
+        println!("  {} ({})", component.name, component.path.display());
     }
 
-    // Send a request to list all components
-    let list_components_request = json!({
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "component.list",
-        "params": {}
-    });
+    // Create a new component
+    let request = CreateComponentRequest {
+        name: "TodoItem".to_string(),
+        path: None,
+        props: Some(vec![
+            ComponentProp {
+                name: "title".to_string(),
+                r#type: "String".to_string(),
+                required: true,
+                default: None,
+            },
+            ComponentProp {
+                name: "completed".to_string(),
+                r#type: "bool".to_string(),
+                required: false,
+                default: Some(json!(false)),
+            },
+        ]),
+        has_children: None,
+        events: Some(vec![ComponentEvent {
+            name: "toggle".to_string(),
+            r#type: "click".to_string(),
+        }]),
+        template: None,
+    };
 
-    ws_stream
-        .send(Message::Text(list_components_request.to_string()))
-        .await?;
-    println!("Sent component list request");
+    let component = client.create_component(request).await?;
+    println!(
+        "Created component {} with ID {}",
+        component.name, component.id
+    );
 
-    // Wait for the response
-    if let Some(Ok(message)) = ws_stream.next().await {
-        if let Message::Text(text) = message {
-            println!("Received component list response: {}", text);
-        }
-    }
-
-    // Send a request to create a new component
-    let create_component_request = json!({
-        "jsonrpc": "2.0",
-        "id": 2,
-        "method": "component.create",
-        "params": {
-            "name": "TodoItem",
-            "props": [
-                {
-                    "name": "title",
-                    "type": "String",
-                    "required": true
-                },
-                {
-                    "name": "completed",
-                    "type": "bool",
-                    "required": false,
-                    "default": false
-                }
-            ],
-            "events": [
-                {
-                    "name": "toggle",
-                    "type": "click"
-                }
-            ]
-        }
-    });
-
-    ws_stream
-        .send(Message::Text(create_component_request.to_string()))
-        .await?;
-    println!("Sent component creation request");
-
-    // Wait for the response
-    if let Some(Ok(message)) = ws_stream.next().await {
-        if let Message::Text(text) = message {
-            println!("Received component creation response: {}", text);
-        }
-    }
-
-    // Send a request for project information
-    let project_info_request = json!({
-        "jsonrpc": "2.0",
-        "id": 3,
-        "method": "project.info",
-        "params": {}
-    });
-
-    ws_stream
-        .send(Message::Text(project_info_request.to_string()))
-        .await?;
-    println!("Sent project info request");
-
-    // Wait for the response
-    if let Some(Ok(message)) = ws_stream.next().await {
-        if let Message::Text(text) = message {
-            println!("Received project info response: {}", text);
-        }
-    }
+    // Get project information
+    let project = client.project_info().await?;
+    println!("Project: {} {}", project.name, project.version);
 
-    // Close the connection
-    ws_stream.close(None).await?;
+    // Dropping the client closes the connection
+    drop(client);
     println!("Closed connection to MCP server");
 
     Ok(())
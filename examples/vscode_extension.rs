@@ -1,10 +1,11 @@
 // Example integration with VS Code extension
 
-use serde_json::json;
+use futures_util::StreamExt;
+use orbit_mcp::api::build::{BuildMode, BuildOptions, BuildTarget};
+use orbit_mcp::api::components::{ComponentProp, CreateComponentRequest};
+use orbit_mcp::client::McpClient;
+use orbit_mcp::utils::auth;
 use std::error::Error;
-use tokio::time::{sleep, Duration};
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
-use url::Url;
 
 #[tokio::main]
 async fn main() -> Result<(), Box<dyn Error>> {
@@ -12,129 +13,91 @@ async fn main() -> Result<(), Box<dyn Error>> {
     println!("VS Code Extension: Connecting to MCP server...");
 
     // Connect to the MCP server
-    let url = Url::parse("ws://localhost:3000/api/ws")?;
-    let (mut ws_stream, _) = connect_async(url).await?;
+    let token = auth::read_token_file(&auth::default_token_path(3000))?;
+    let client =
+        McpClient::connect_websocket_with_token("ws://localhost:3000/api/ws", &token).await?;
     println!("VS Code Extension: Connected to MCP server!");
 
-    // Wait for the welcome message
-    if let Some(Ok(message)) = ws_stream.next().await {
-        if let Message::Text(text) = message {
-            println!("VS Code Extension: Received welcome message: {}", text);
+    // Listen for server events while the user works
+    let events = client.events();
+    let event_listener = tokio::spawn(async move {
+        let mut events = Box::pin(events);
+        while let Some(event) = events.next().await {
+            println!(
+                "VS Code Extension: Received server event {}: {}",
+                event.event_type, event.payload
+            );
         }
-    }
+    });
 
     // Simulate user actions in VS Code
     println!("\nUser opens Orbit project in VS Code");
 
-    // Send a request for project information
-    let project_info_request = json!({
-        "jsonrpc": "2.0",
-        "id": 1,
-        "method": "project.info",
-        "params": {}
-    });
-
-    ws_stream
-        .send(Message::Text(project_info_request.to_string()))
-        .await?;
-
-    // Wait for the response
-    if let Some(Ok(message)) = ws_stream.next().await {
-        if let Message::Text(text) = message {
-            println!("VS Code Extension: Received project structure");
-            println!("VS Code: Displaying project structure in Explorer view");
-        }
-    }
+    let project = client.project_info().await?;
+    println!(
+        "VS Code Extension: Received project structure for {}",
+        project.name
+    );
+    println!("VS Code: Displaying project structure in Explorer view");
 
     // Simulate user requesting to create a new component via VS Code command
     println!("\nUser executes 'Create New Component' command");
 
-    // Send a request to create a new component
-    let create_component_request = json!({
-        "jsonrpc": "2.0",
-        "id": 2,
-        "method": "component.create",
-        "params": {
-            "name": "NavBar",
-            "template": "basic",
-            "path": "src/components",
-            "props": [
-                {
-                    "name": "title",
-                    "type": "String",
-                    "required": true
-                },
-                {
-                    "name": "items",
-                    "type": "Vec<MenuItem>",
-                    "required": true
-                }
-            ],
-            "has_children": true
-        }
-    });
-
-    ws_stream
-        .send(Message::Text(create_component_request.to_string()))
-        .await?;
-
-    // Wait for the response
-    if let Some(Ok(message)) = ws_stream.next().await {
-        if let Message::Text(text) = message {
-            println!("VS Code Extension: Component created successfully");
-            println!("VS Code: Opening component in editor");
-        }
-    }
+    let request = CreateComponentRequest {
+        name: "NavBar".to_string(),
+        path: Some("src/components".to_string()),
+        props: Some(vec![
+            ComponentProp {
+                name: "title".to_string(),
+                r#type: "String".to_string(),
+                required: true,
+                default: None,
+            },
+            ComponentProp {
+                name: "items".to_string(),
+                r#type: "Vec<MenuItem>".to_string(),
+                required: true,
+                default: None,
+            },
+        ]),
+        has_children: Some(true),
+        events: None,
+        template: Some("basic".to_string()),
+    };
+
+    let component = client.create_component(request).await?;
+    println!(
+        "VS Code Extension: Component {} created successfully",
+        component.name
+    );
+    println!("VS Code: Opening component in editor");
 
     // Simulate user building the project
     println!("\nUser executes 'Build Project' command");
-
-    // Send a request to build the project
-    let build_request = json!({
-        "jsonrpc": "2.0",
-        "id": 3,
-        "method": "build.compile",
-        "params": {
-            "options": {
-                "target": "web",
-                "mode": "development",
-                "optimize": false,
-                "source_maps": true,
-                "features": []
-            }
-        }
-    });
-
-    ws_stream
-        .send(Message::Text(build_request.to_string()))
-        .await?;
-
-    // Simulate build in progress
     println!("VS Code: Showing build progress in status bar");
-    sleep(Duration::from_millis(1000)).await;
-
-    // Wait for the response
-    if let Some(Ok(message)) = ws_stream.next().await {
-        if let Message::Text(text) = message {
-            println!("VS Code Extension: Build completed");
-            println!("VS Code: Displaying build results in Problems panel");
-        }
-    }
 
-    // Simulate the VS Code extension handling server events
-    println!("\nServer sends component change notification");
+    let result = client
+        .build(BuildOptions {
+            target: BuildTarget::Web,
+            mode: BuildMode::Development,
+            optimize: false,
+            source_maps: true,
+            features: vec![],
+        })
+        .await?;
 
-    // Wait for any server event
-    if let Some(Ok(message)) = ws_stream.next().await {
-        if let Message::Text(text) = message {
-            println!("VS Code Extension: Received server event: {}", text);
-            println!("VS Code: Updating Explorer view to reflect changes");
-        }
-    }
+    println!(
+        "VS Code Extension: Build completed in {} ms ({} errors, {} warnings)",
+        result.time_ms,
+        result.errors.len(),
+        result.warnings.len()
+    );
+    println!("VS Code: Displaying build results in Problems panel");
 
     // Simulate user closing VS Code
     println!("\nUser closes VS Code");
-    ws_stream.close(None).await?;
+    drop(client);
+    event_listener.abort();
     println!("VS Code Extension: Disconnected from MCP server");
 
     Ok(())
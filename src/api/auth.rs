@@ -0,0 +1,130 @@
+// Authorization models (what a client is allowed to do)
+
+use std::collections::BTreeSet;
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Serialize};
+
+/// A permission needed by some operations
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Scope {
+    /// List and read components, projects, docs, diagnostics and results
+    Read,
+    /// Create, update and delete components and projects
+    Write,
+    /// Run builds
+    Build,
+    /// Run tests
+    Test,
+    /// Everything, including what the other scopes allow
+    Admin,
+}
+
+impl Scope {
+    /// Every scope
+    pub const ALL: [Scope; 5] = [
+        Scope::Read,
+        Scope::Write,
+        Scope::Build,
+        Scope::Test,
+        Scope::Admin,
+    ];
+
+    /// Whether operations needing the scope change the project or the server
+    pub fn is_mutating(self) -> bool {
+        self != Scope::Read
+    }
+}
+
+impl fmt::Display for Scope {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Scope::Read => "read",
+            Scope::Write => "write",
+            Scope::Build => "build",
+            Scope::Test => "test",
+            Scope::Admin => "admin",
+        };
+        f.write_str(name)
+    }
+}
+
+impl FromStr for Scope {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Scope::ALL
+            .into_iter()
+            .find(|scope| scope.to_string() == s.trim())
+            .ok_or_else(|| {
+                format!(
+                    "unknown scope {:?} (expected read, write, build, test or admin)",
+                    s
+                )
+            })
+    }
+}
+
+/// A set of scopes, such as `read,test`
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Scopes(BTreeSet<Scope>);
+
+impl Scopes {
+    /// Every scope
+    pub fn all() -> Self {
+        Self(Scope::ALL.into_iter().collect())
+    }
+
+    /// Whether operations needing `scope` are allowed
+    pub fn allows(&self, scope: Scope) -> bool {
+        self.0.contains(&scope) || self.0.contains(&Scope::Admin)
+    }
+
+    /// The scopes that don't change anything
+    pub fn read_only(&self) -> Self {
+        Scope::ALL
+            .into_iter()
+            .filter(|scope| !scope.is_mutating() && self.allows(*scope))
+            .collect()
+    }
+
+    /// The scopes allowed by both sets
+    pub fn intersection(&self, other: &Scopes) -> Self {
+        Scope::ALL
+            .into_iter()
+            .filter(|scope| self.allows(*scope) && other.allows(*scope))
+            .collect()
+    }
+
+    /// Iterate over the scopes in order
+    pub fn iter(&self) -> impl Iterator<Item = Scope> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl FromIterator<Scope> for Scopes {
+    fn from_iter<I: IntoIterator<Item = Scope>>(iter: I) -> Self {
+        Self(iter.into_iter().collect())
+    }
+}
+
+impl fmt::Display for Scopes {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<String> = self.iter().map(|scope| scope.to_string()).collect();
+        f.write_str(&names.join(","))
+    }
+}
+
+impl FromStr for Scopes {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        s.split(',')
+            .filter(|scope| !scope.trim().is_empty())
+            .map(Scope::from_str)
+            .collect()
+    }
+}
@@ -1,10 +1,11 @@
 // Build-related API models
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Build options
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BuildOptions {
     /// Build target
     pub target: BuildTarget,
@@ -19,7 +20,7 @@ pub struct BuildOptions {
 }
 
 /// Build target
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum BuildTarget {
     /// Web target
     #[serde(rename = "web")]
@@ -33,7 +34,7 @@ pub enum BuildTarget {
 }
 
 /// Build mode
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum BuildMode {
     /// Development mode
     #[serde(rename = "development")]
@@ -44,7 +45,7 @@ pub enum BuildMode {
 }
 
 /// Build result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BuildResult {
     /// Whether the build was successful
     pub success: bool,
@@ -59,7 +60,7 @@ pub struct BuildResult {
 }
 
 /// Build error
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BuildError {
     /// Error code
     pub code: String,
@@ -70,7 +71,7 @@ pub struct BuildError {
 }
 
 /// Build warning
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BuildWarning {
     /// Warning code
     pub code: String,
@@ -81,7 +82,7 @@ pub struct BuildWarning {
 }
 
 /// Build location
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BuildLocation {
     /// File path
     pub file: PathBuf,
@@ -92,7 +93,7 @@ pub struct BuildLocation {
 }
 
 /// Build request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct BuildRequest {
     /// Build options
     pub options: BuildOptions,
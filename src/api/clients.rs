@@ -0,0 +1,33 @@
+// Client identification models
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::api::mcp::Implementation;
+
+/// Hello sent by clients that don't speak the MCP lifecycle
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientHelloRequest {
+    /// Client name
+    pub name: String,
+    /// Client version
+    #[serde(default)]
+    pub version: Option<String>,
+    /// Protocol version the client speaks
+    #[serde(default)]
+    pub protocol_version: Option<String>,
+    /// Capabilities the client supports, such as `events` or `sampling`
+    #[serde(default)]
+    pub capabilities: Vec<String>,
+}
+
+/// Reply to a client hello
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct ClientHelloResult {
+    /// ID the server knows the client by
+    pub client_id: String,
+    /// Server implementation details
+    pub server_info: Implementation,
+}
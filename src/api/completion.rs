@@ -0,0 +1,82 @@
+// MCP completion models (argument autocompletion)
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// Most values returned by a single completion
+pub const MAX_COMPLETION_VALUES: usize = 100;
+
+/// `completion/complete` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteRequest {
+    /// What the argument belongs to
+    #[serde(rename = "ref")]
+    pub reference: Reference,
+    /// The argument being completed
+    pub argument: CompleteArgument,
+    /// Values of the other arguments filled in so far
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub context: Option<CompleteContext>,
+}
+
+/// What a completed argument belongs to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Reference {
+    /// A prompt, by name
+    #[serde(rename = "ref/prompt")]
+    Prompt {
+        /// Prompt name
+        name: String,
+    },
+    /// A resource template, by URI template
+    #[serde(rename = "ref/resource")]
+    Resource {
+        /// URI template, such as `orbit://component/{id}`
+        uri: String,
+    },
+    /// A tool, by name (an extension; MCP only defines prompts and resources)
+    #[serde(rename = "ref/tool")]
+    Tool {
+        /// Tool name, or the JSON-RPC method it wraps
+        name: String,
+    },
+}
+
+/// The argument being completed
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CompleteArgument {
+    /// Argument name; nested tool arguments may use a path such as `options.filter`
+    pub name: String,
+    /// What has been typed so far
+    pub value: String,
+}
+
+/// Context of a completion
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompleteContext {
+    /// Values of the other arguments, by name
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+/// `completion/complete` result
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompleteResult {
+    /// The suggestions
+    pub completion: Completion,
+}
+
+/// Suggested values, best match first
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Completion {
+    /// Suggested values, at most 100
+    pub values: Vec<String>,
+    /// Number of matching values, which may exceed the values returned
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<usize>,
+    /// Whether there are more matches than returned
+    #[serde(default)]
+    pub has_more: bool,
+}
@@ -1,10 +1,11 @@
 // Component-related API models
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Component information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Component {
     /// Component ID
     pub id: String,
@@ -21,7 +22,7 @@ pub struct Component {
 }
 
 /// Component property
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentProp {
     /// Property name
     pub name: String,
@@ -34,7 +35,7 @@ pub struct ComponentProp {
 }
 
 /// Component event
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentEvent {
     /// Event name
     pub name: String,
@@ -43,7 +44,7 @@ pub struct ComponentEvent {
 }
 
 /// Create component request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CreateComponentRequest {
     /// Component name
     pub name: String,
@@ -60,7 +61,7 @@ pub struct CreateComponentRequest {
 }
 
 /// Update component request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UpdateComponentRequest {
     /// Component properties to update
     pub props: Option<Vec<ComponentProp>>,
@@ -74,8 +75,25 @@ pub struct UpdateComponentRequest {
     pub style: Option<String>,
 }
 
+/// Request that references a component by ID
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ComponentIdRequest {
+    /// Component ID
+    pub id: String,
+}
+
+/// Update component request addressed by component ID
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct UpdateComponentByIdRequest {
+    /// Component ID
+    pub id: String,
+    /// Fields to update
+    #[serde(flatten)]
+    pub update: UpdateComponentRequest,
+}
+
 /// Component analysis result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentAnalysis {
     /// Component complexity score (0-100)
     pub complexity: u8,
@@ -88,7 +106,7 @@ pub struct ComponentAnalysis {
 }
 
 /// Issue found in a component
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentIssue {
     /// Issue code
     pub code: String,
@@ -101,7 +119,7 @@ pub struct ComponentIssue {
 }
 
 /// Location in a component
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ComponentLocation {
     /// File path
     pub file: PathBuf,
@@ -112,7 +130,7 @@ pub struct ComponentLocation {
 }
 
 /// Issue severity
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub enum IssueSeverity {
     /// Error severity
     #[serde(rename = "error")]
@@ -1,10 +1,11 @@
 // Documentation-related API models
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Documentation query request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DocQueryRequest {
     /// Search query
     pub query: String,
@@ -15,7 +16,7 @@ pub struct DocQueryRequest {
 }
 
 /// Documentation category
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, JsonSchema)]
 pub enum DocCategory {
     /// API documentation
     #[serde(rename = "api")]
@@ -32,7 +33,7 @@ pub enum DocCategory {
 }
 
 /// Documentation item
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DocItem {
     /// Documentation title
     pub title: String,
@@ -49,7 +50,7 @@ pub struct DocItem {
 }
 
 /// Documentation example request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DocExampleRequest {
     /// Type of example (component, project, feature)
     pub example_type: String,
@@ -60,7 +61,7 @@ pub struct DocExampleRequest {
 }
 
 /// Documentation example
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct DocExample {
     /// Example title
     pub title: String,
@@ -0,0 +1,31 @@
+// Event subscription models
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Event subscription request
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct SubscribeEventsRequest {
+    /// Event type patterns, such as `build.*` or `file.changed`
+    pub events: Vec<String>,
+    /// Globs that file events must match, relative to the project root
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+/// Event subscription result
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscribeEventsResult {
+    /// ID for cancelling the subscription later
+    pub subscription_id: String,
+}
+
+/// Event unsubscription request
+#[derive(Debug, Clone, Default, Serialize, Deserialize, JsonSchema)]
+#[serde(rename_all = "camelCase")]
+pub struct UnsubscribeEventsRequest {
+    /// Subscription to cancel, or all of them if omitted
+    #[serde(default)]
+    pub subscription_id: Option<String>,
+}
@@ -0,0 +1,182 @@
+// JSON-RPC 2.0 message models
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// JSON-RPC protocol version
+pub const JSONRPC_VERSION: &str = "2.0";
+
+/// Invalid JSON was received
+pub const PARSE_ERROR: i64 = -32700;
+/// The JSON sent is not a valid request object
+pub const INVALID_REQUEST: i64 = -32600;
+/// The method does not exist or is not available
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// Invalid method parameters
+pub const INVALID_PARAMS: i64 = -32602;
+/// Internal server error
+pub const INTERNAL_ERROR: i64 = -32603;
+/// Generic server error (start of the implementation-defined range)
+pub const SERVER_ERROR: i64 = -32000;
+/// A referenced component or project does not exist
+pub const NOT_FOUND: i64 = -32001;
+/// A requested MCP resource does not exist
+pub const RESOURCE_NOT_FOUND: i64 = -32002;
+/// The request didn't carry a valid token
+pub const UNAUTHORIZED: i64 = -32003;
+/// The client's scopes don't allow the request
+pub const FORBIDDEN: i64 = -32004;
+/// The request was cancelled by the client (as in LSP)
+pub const REQUEST_CANCELLED: i64 = -32800;
+
+/// JSON-RPC request or notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcRequest {
+    /// Protocol version, always "2.0"
+    pub jsonrpc: String,
+    /// Request ID (absent for notifications)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub id: Option<Value>,
+    /// Method name
+    pub method: String,
+    /// Method parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+/// JSON-RPC response
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcResponse {
+    /// Protocol version, always "2.0"
+    pub jsonrpc: String,
+    /// ID of the request this response belongs to
+    pub id: Value,
+    /// Result (present on success)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub result: Option<Value>,
+    /// Error (present on failure)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<JsonRpcError>,
+}
+
+impl JsonRpcResponse {
+    /// Create a successful response
+    pub fn success(id: Value, result: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: Some(result),
+            error: None,
+        }
+    }
+
+    /// Create an error response
+    pub fn failure(id: Value, error: JsonRpcError) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id,
+            result: None,
+            error: Some(error),
+        }
+    }
+}
+
+/// Reply to a JSON-RPC frame: one response, or one per request in a batch
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum JsonRpcReply {
+    /// Response to a single request
+    Single(JsonRpcResponse),
+    /// Responses to the requests in a batch
+    Batch(Vec<JsonRpcResponse>),
+}
+
+/// JSON-RPC error object
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct JsonRpcError {
+    /// Error code
+    pub code: i64,
+    /// Error message
+    pub message: String,
+    /// Additional error data
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub data: Option<Value>,
+}
+
+impl JsonRpcError {
+    /// Create a new error with the given code and message
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// Attach additional data to the error
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = Some(data);
+        self
+    }
+
+    /// Invalid JSON was received
+    pub fn parse_error(detail: impl Into<String>) -> Self {
+        Self::new(PARSE_ERROR, "Parse error").with_data(Value::String(detail.into()))
+    }
+
+    /// The message is not a valid request object
+    pub fn invalid_request(detail: impl Into<String>) -> Self {
+        Self::new(INVALID_REQUEST, "Invalid Request").with_data(Value::String(detail.into()))
+    }
+
+    /// The requested method does not exist
+    pub fn method_not_found(method: &str) -> Self {
+        Self::new(METHOD_NOT_FOUND, format!("Method not found: {}", method))
+    }
+
+    /// The method parameters are invalid
+    pub fn invalid_params(detail: impl Into<String>) -> Self {
+        Self::new(INVALID_PARAMS, format!("Invalid params: {}", detail.into()))
+    }
+
+    /// The request was cancelled before it finished
+    pub fn request_cancelled() -> Self {
+        Self::new(REQUEST_CANCELLED, "Request cancelled")
+    }
+
+    /// Internal server error
+    pub fn internal_error(detail: impl Into<String>) -> Self {
+        Self::new(INTERNAL_ERROR, format!("Internal error: {}", detail.into()))
+    }
+}
+
+/// JSON-RPC notification sent from the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct JsonRpcNotification {
+    /// Protocol version, always "2.0"
+    pub jsonrpc: String,
+    /// Notification method
+    pub method: String,
+    /// Notification parameters
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub params: Option<Value>,
+}
+
+impl JsonRpcNotification {
+    /// Create a new notification
+    pub fn new(method: impl Into<String>, params: Value) -> Self {
+        Self {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            method: method.into(),
+            params: Some(params),
+        }
+    }
+}
+
+/// Parameters of an LSP-style `$/cancelRequest` notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CancelParams {
+    /// ID of the request to cancel
+    pub id: Value,
+}
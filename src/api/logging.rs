@@ -0,0 +1,68 @@
+// MCP logging models
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Severity of a log message, as defined by MCP (after syslog)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum LoggingLevel {
+    /// Detailed debugging information
+    Debug,
+    /// General information
+    Info,
+    /// Normal but significant events
+    Notice,
+    /// Warning conditions
+    Warning,
+    /// Error conditions
+    Error,
+    /// Critical conditions
+    Critical,
+    /// Action must be taken immediately
+    Alert,
+    /// The system is unusable
+    Emergency,
+}
+
+impl From<log::Level> for LoggingLevel {
+    fn from(level: log::Level) -> Self {
+        match level {
+            log::Level::Error => LoggingLevel::Error,
+            log::Level::Warn => LoggingLevel::Warning,
+            log::Level::Info => LoggingLevel::Info,
+            log::Level::Debug | log::Level::Trace => LoggingLevel::Debug,
+        }
+    }
+}
+
+impl LoggingLevel {
+    /// Most verbose `log` filter that produces records at this level or above
+    pub fn log_filter(self) -> log::LevelFilter {
+        match self {
+            LoggingLevel::Debug => log::LevelFilter::Debug,
+            LoggingLevel::Info | LoggingLevel::Notice => log::LevelFilter::Info,
+            LoggingLevel::Warning => log::LevelFilter::Warn,
+            _ => log::LevelFilter::Error,
+        }
+    }
+}
+
+/// `logging/setLevel` request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetLevelRequest {
+    /// Minimum level of the log messages to send to the client
+    pub level: LoggingLevel,
+}
+
+/// Params of a `notifications/message` notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoggingMessageParams {
+    /// Message severity
+    pub level: LoggingLevel,
+    /// Name of the logger that emitted the message
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logger: Option<String>,
+    /// Message payload
+    pub data: Value,
+}
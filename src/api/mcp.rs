@@ -0,0 +1,195 @@
+// Model Context Protocol lifecycle and tool models
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+/// Latest MCP protocol revision supported by the server
+pub const LATEST_PROTOCOL_VERSION: &str = "2025-06-18";
+
+/// All MCP protocol revisions supported by the server, newest first
+pub const SUPPORTED_PROTOCOL_VERSIONS: &[&str] = &["2025-06-18", "2025-03-26", "2024-11-05"];
+
+/// Name and version of an MCP implementation
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct Implementation {
+    /// Implementation name
+    pub name: String,
+    /// Implementation version
+    pub version: String,
+}
+
+/// Initialize request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeRequest {
+    /// Protocol version requested by the client
+    pub protocol_version: String,
+    /// Capabilities declared by the client
+    #[serde(default)]
+    pub capabilities: Value,
+    /// Client implementation details
+    pub client_info: Implementation,
+}
+
+/// Initialize result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InitializeResult {
+    /// Negotiated protocol version
+    pub protocol_version: String,
+    /// Capabilities offered by the server
+    pub capabilities: ServerCapabilities,
+    /// Server implementation details
+    pub server_info: Implementation,
+    /// Usage hints for the connected model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instructions: Option<String>,
+}
+
+/// Capabilities offered by the server
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerCapabilities {
+    /// Tool support
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tools: Option<ToolsCapability>,
+    /// Resource support
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourcesCapability>,
+    /// Prompt support
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prompts: Option<PromptsCapability>,
+    /// Log message support
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub logging: Option<LoggingCapability>,
+    /// Argument completion support
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub completions: Option<CompletionsCapability>,
+}
+
+/// Logging capability details (the server takes `logging/setLevel`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct LoggingCapability {}
+
+/// Completion capability details (the server takes `completion/complete`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CompletionsCapability {}
+
+/// Tool capability details
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolsCapability {
+    /// Whether the server notifies clients when the tool list changes
+    pub list_changed: bool,
+}
+
+/// Resource capability details
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourcesCapability {
+    /// Whether clients can subscribe to resource updates
+    pub subscribe: bool,
+    /// Whether the server notifies clients when the resource list changes
+    pub list_changed: bool,
+}
+
+/// Prompt capability details
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PromptsCapability {
+    /// Whether the server notifies clients when the prompt list changes
+    pub list_changed: bool,
+}
+
+/// Tool definition
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Tool {
+    /// Tool name
+    pub name: String,
+    /// Human-readable description
+    pub description: String,
+    /// JSON Schema for the tool arguments
+    pub input_schema: Value,
+}
+
+/// Tool list result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListToolsResult {
+    /// Available tools
+    pub tools: Vec<Tool>,
+}
+
+/// Tool call request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CallToolRequest {
+    /// Tool name
+    pub name: String,
+    /// Tool arguments
+    #[serde(default)]
+    pub arguments: Option<Value>,
+}
+
+/// Tool call result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CallToolResult {
+    /// Unstructured result content
+    pub content: Vec<Content>,
+    /// Structured result (the JSON-RPC method result)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub structured_content: Option<Value>,
+    /// Whether the tool call failed
+    #[serde(default)]
+    pub is_error: bool,
+}
+
+/// Content item in a tool result or message
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum Content {
+    /// Text content
+    #[serde(rename = "text")]
+    Text {
+        /// The text
+        text: String,
+    },
+    /// Image content
+    #[serde(rename = "image")]
+    Image {
+        /// Base64-encoded image data
+        data: String,
+        /// Image MIME type, such as `image/png`
+        #[serde(rename = "mimeType")]
+        mime_type: String,
+    },
+}
+
+/// Parameters of a `notifications/progress` notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProgressParams {
+    /// Token from the `_meta.progressToken` of the request being reported on
+    pub progress_token: Value,
+    /// Progress so far, increasing with every notification
+    pub progress: f64,
+    /// Total amount of work, if known
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+    /// Description of the current step
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+/// Parameters of a `notifications/cancelled` notification
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CancelledParams {
+    /// ID of the request to cancel
+    pub request_id: Value,
+    /// Why the request was cancelled
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
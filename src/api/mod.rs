@@ -1,9 +1,21 @@
 // API module definition
 
+pub mod auth;
 pub mod build;
+pub mod clients;
+pub mod completion;
 pub mod components;
 pub mod documentation;
+pub mod events;
+pub mod jsonrpc;
+pub mod logging;
+pub mod mcp;
 pub mod projects;
+pub mod prompts;
+pub mod resources;
+pub mod roots;
+pub mod sampling;
+pub mod schema;
 pub mod testing;
 
 // Common API types
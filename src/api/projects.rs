@@ -1,10 +1,11 @@
 // Project-related API models
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 
 /// Project information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Project {
     /// Project name
     pub name: String,
@@ -21,7 +22,7 @@ pub struct Project {
 }
 
 /// Dependency information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct Dependency {
     /// Dependency name
     pub name: String,
@@ -32,7 +33,7 @@ pub struct Dependency {
 }
 
 /// Project structure information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectStructure {
     /// Project components
     pub components: Vec<String>,
@@ -42,8 +43,20 @@ pub struct ProjectStructure {
     pub tests: Vec<String>,
 }
 
+/// A project the server can work on, one per client root
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct ProjectRoot {
+    /// Name to address the project by
+    pub name: String,
+    /// Project directory
+    pub path: PathBuf,
+    /// Root URI declared by the client, if the project came from one
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uri: Option<String>,
+}
+
 /// Create project request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct CreateProjectRequest {
     /// Project name
     pub name: String,
@@ -58,7 +71,7 @@ pub struct CreateProjectRequest {
 }
 
 /// Update project request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct UpdateProjectRequest {
     /// Project name
     pub name: Option<String>,
@@ -73,7 +86,7 @@ pub struct UpdateProjectRequest {
 }
 
 /// Project analysis result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectAnalysis {
     /// Number of components
     pub component_count: usize,
@@ -90,7 +103,7 @@ pub struct ProjectAnalysis {
 }
 
 /// Issue found in a project
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectIssue {
     /// Issue code
     pub code: String,
@@ -103,7 +116,7 @@ pub struct ProjectIssue {
 }
 
 /// Location in a project
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct ProjectLocation {
     /// File path
     pub file: PathBuf,
@@ -114,7 +127,7 @@ pub struct ProjectLocation {
 }
 
 /// Issue severity
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, JsonSchema)]
 pub enum IssueSeverity {
     /// Error severity
     #[serde(rename = "error")]
@@ -0,0 +1,110 @@
+// MCP prompt models
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::api::mcp::Content;
+
+/// A prompt offered by the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Prompt {
+    /// Prompt name
+    pub name: String,
+    /// Prompt description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Arguments the prompt accepts
+    #[serde(default)]
+    pub arguments: Vec<PromptArgument>,
+}
+
+/// An argument accepted by a prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptArgument {
+    /// Argument name
+    pub name: String,
+    /// Argument description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Whether the argument must be provided
+    #[serde(default)]
+    pub required: bool,
+    /// Argument type, used to validate values (not part of the MCP schema)
+    #[serde(rename = "type", default, skip_serializing)]
+    pub kind: PromptArgumentKind,
+}
+
+/// Type of a prompt argument
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PromptArgumentKind {
+    /// Free-form text
+    #[default]
+    String,
+    /// ID of a component in the project; its source is available to the template
+    Component,
+    /// Whole number
+    Integer,
+    /// `true` or `false`
+    Boolean,
+}
+
+/// A prompt definition, as built in or loaded from `.orbit/prompts/*.json`
+///
+/// The template may reference arguments as `{{name}}`, the source of a
+/// component argument as `{{name.source}}`, and the live project context as
+/// `{{build_errors}}` and `{{test_failures}}`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptTemplate {
+    /// Prompt metadata
+    #[serde(flatten)]
+    pub prompt: Prompt,
+    /// Text of the user message
+    pub template: String,
+}
+
+/// Prompt list result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListPromptsResult {
+    /// Available prompts
+    pub prompts: Vec<Prompt>,
+}
+
+/// Prompt get request
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetPromptRequest {
+    /// Prompt name
+    pub name: String,
+    /// Argument values
+    #[serde(default)]
+    pub arguments: HashMap<String, String>,
+}
+
+/// Prompt get result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct GetPromptResult {
+    /// Prompt description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Messages to send to the model
+    pub messages: Vec<PromptMessage>,
+}
+
+/// A message in a rendered prompt
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromptMessage {
+    /// Speaker of the message
+    pub role: Role,
+    /// Message content
+    pub content: Content,
+}
+
+/// Speaker of a prompt message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Role {
+    /// The user
+    User,
+    /// The model
+    Assistant,
+}
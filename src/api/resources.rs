@@ -0,0 +1,77 @@
+// MCP resource models
+
+use serde::{Deserialize, Serialize};
+
+/// A readable resource exposed by the server
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Resource {
+    /// Resource URI
+    pub uri: String,
+    /// Resource name
+    pub name: String,
+    /// Resource description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MIME type of the resource contents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// A parameterized family of resources
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceTemplate {
+    /// RFC 6570 URI template
+    pub uri_template: String,
+    /// Template name
+    pub name: String,
+    /// Template description
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// MIME type of matching resources
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+}
+
+/// Contents of a resource
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ResourceContents {
+    /// Resource URI
+    pub uri: String,
+    /// MIME type of the contents
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mime_type: Option<String>,
+    /// Text contents
+    pub text: String,
+}
+
+/// Resource list result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListResourcesResult {
+    /// Available resources
+    pub resources: Vec<Resource>,
+}
+
+/// Resource template list result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ListResourceTemplatesResult {
+    /// Available resource templates
+    pub resource_templates: Vec<ResourceTemplate>,
+}
+
+/// Request that references a resource by URI (read, subscribe, unsubscribe)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ResourceUriRequest {
+    /// Resource URI
+    pub uri: String,
+}
+
+/// Resource read result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReadResourceResult {
+    /// Resource contents
+    pub contents: Vec<ResourceContents>,
+}
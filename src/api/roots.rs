@@ -0,0 +1,20 @@
+// MCP roots models (directories the client has open)
+
+use serde::{Deserialize, Serialize};
+
+/// A root directory declared by the client
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Root {
+    /// Root URI, a `file://` URI
+    pub uri: String,
+    /// Display name
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+}
+
+/// `roots/list` result
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListRootsResult {
+    /// The client's roots
+    pub roots: Vec<Root>,
+}
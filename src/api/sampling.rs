@@ -0,0 +1,94 @@
+// MCP sampling models (requests the server sends to the client's model)
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::api::mcp::Content;
+use crate::api::prompts::Role;
+
+/// `sampling/createMessage` request
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageRequest {
+    /// Conversation to continue
+    pub messages: Vec<SamplingMessage>,
+    /// Hints for picking a model
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model_preferences: Option<ModelPreferences>,
+    /// System prompt the client may use
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub system_prompt: Option<String>,
+    /// Context to include from MCP servers: `none`, `thisServer` or `allServers`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub include_context: Option<String>,
+    /// Sampling temperature
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub temperature: Option<f64>,
+    /// Maximum number of tokens to generate
+    pub max_tokens: u32,
+    /// Sequences that stop generation
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_sequences: Option<Vec<String>>,
+    /// Provider-specific metadata
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<Value>,
+}
+
+/// Message in a sampling conversation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SamplingMessage {
+    /// Speaker of the message
+    pub role: Role,
+    /// Message content
+    pub content: Content,
+}
+
+impl SamplingMessage {
+    /// Create a text message from the user
+    pub fn user(text: impl Into<String>) -> Self {
+        Self {
+            role: Role::User,
+            content: Content::Text { text: text.into() },
+        }
+    }
+}
+
+/// Model selection hints, each priority between 0 and 1
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ModelPreferences {
+    /// Preferred model names, in order
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub hints: Vec<ModelHint>,
+    /// How much to favour cheap models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cost_priority: Option<f64>,
+    /// How much to favour fast models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub speed_priority: Option<f64>,
+    /// How much to favour capable models
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub intelligence_priority: Option<f64>,
+}
+
+/// Model name hint
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModelHint {
+    /// Full or partial model name
+    pub name: String,
+}
+
+/// `sampling/createMessage` result
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateMessageResult {
+    /// Speaker of the generated message
+    pub role: Role,
+    /// Generated content
+    pub content: Content,
+    /// Model that generated the message
+    pub model: String,
+    /// Why generation stopped, such as `endTurn` or `maxTokens`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub stop_reason: Option<String>,
+}
@@ -0,0 +1,456 @@
+// JSON Schema and OpenAPI generated from the API models
+
+use schemars::gen::{SchemaGenerator, SchemaSettings};
+use schemars::schema::{InstanceType, Schema, SchemaObject};
+use schemars::JsonSchema;
+use serde_json::{json, Map, Value};
+
+use crate::api::build::{BuildError, BuildRequest, BuildResult};
+use crate::api::clients::{ClientHelloRequest, ClientHelloResult};
+use crate::api::components::{
+    Component, ComponentAnalysis, ComponentIdRequest, CreateComponentRequest,
+    UpdateComponentByIdRequest, UpdateComponentRequest,
+};
+use crate::api::documentation::{DocExample, DocExampleRequest, DocItem, DocQueryRequest};
+use crate::api::events::{SubscribeEventsRequest, SubscribeEventsResult, UnsubscribeEventsRequest};
+use crate::api::jsonrpc::JsonRpcError;
+use crate::api::projects::{CreateProjectRequest, Project, ProjectRoot, UpdateProjectRequest};
+use crate::api::testing::{TestRequest, TestResult};
+
+/// Version of the OpenAPI specification the document follows
+pub const OPENAPI_VERSION: &str = "3.0.3";
+
+// Builds the schema for a request or response body
+type SchemaFn = fn(&mut SchemaGenerator) -> Schema;
+
+/// A JSON-RPC method with the schemas of its params and result
+pub struct RpcMethod {
+    /// Method name
+    pub name: &'static str,
+    /// What the method does
+    pub description: &'static str,
+    params: SchemaFn,
+    result: SchemaFn,
+}
+
+/// The JSON-RPC methods backed by the API models
+///
+/// MCP methods, such as `initialize` or `tools/call`, follow the schemas of
+/// the MCP specification instead.
+pub const RPC_METHODS: &[RpcMethod] = &[
+    RpcMethod {
+        name: "client.hello",
+        description: "Introduce the client without the MCP lifecycle",
+        params: schema::<ClientHelloRequest>,
+        result: schema::<ClientHelloResult>,
+    },
+    RpcMethod {
+        name: "events.subscribe",
+        description: "Subscribe to server events matching some patterns",
+        params: schema::<SubscribeEventsRequest>,
+        result: schema::<SubscribeEventsResult>,
+    },
+    RpcMethod {
+        name: "events.unsubscribe",
+        description: "Cancel an event subscription, or all of them",
+        params: schema::<UnsubscribeEventsRequest>,
+        result: any_object,
+    },
+    RpcMethod {
+        name: "component.list",
+        description: "List all components in the project",
+        params: any_object,
+        result: |gen| wrapped::<Vec<Component>>(gen, "components"),
+    },
+    RpcMethod {
+        name: "component.get",
+        description: "Get details of a component",
+        params: schema::<ComponentIdRequest>,
+        result: |gen| wrapped::<Component>(gen, "component"),
+    },
+    RpcMethod {
+        name: "component.create",
+        description: "Create a new component",
+        params: schema::<CreateComponentRequest>,
+        result: |gen| wrapped::<Component>(gen, "component"),
+    },
+    RpcMethod {
+        name: "component.update",
+        description: "Update an existing component",
+        params: schema::<UpdateComponentByIdRequest>,
+        result: |gen| wrapped::<Component>(gen, "component"),
+    },
+    RpcMethod {
+        name: "component.delete",
+        description: "Delete a component",
+        params: schema::<ComponentIdRequest>,
+        result: deleted,
+    },
+    RpcMethod {
+        name: "component.analyze",
+        description: "Analyze a component for issues and complexity",
+        params: schema::<ComponentIdRequest>,
+        result: |gen| wrapped::<ComponentAnalysis>(gen, "analysis"),
+    },
+    RpcMethod {
+        name: "component.describe",
+        description:
+            "Describe a component in prose, written by the client's model (needs sampling)",
+        params: schema::<ComponentIdRequest>,
+        result: |gen| wrapped::<String>(gen, "description"),
+    },
+    RpcMethod {
+        name: "project.list",
+        description: "List the projects the client can address, one per root it has open",
+        params: any_object,
+        result: |gen| wrapped::<Vec<ProjectRoot>>(gen, "projects"),
+    },
+    RpcMethod {
+        name: "project.info",
+        description: "Get project information",
+        params: any_object,
+        result: |gen| wrapped::<Project>(gen, "project"),
+    },
+    RpcMethod {
+        name: "project.create",
+        description: "Create a new project",
+        params: schema::<CreateProjectRequest>,
+        result: |gen| wrapped::<Project>(gen, "project"),
+    },
+    RpcMethod {
+        name: "project.update",
+        description: "Update project configuration",
+        params: schema::<UpdateProjectRequest>,
+        result: |gen| wrapped::<Project>(gen, "project"),
+    },
+    RpcMethod {
+        name: "build.compile",
+        description: "Compile the project",
+        params: schema::<BuildRequest>,
+        result: |gen| wrapped::<BuildResult>(gen, "result"),
+    },
+    RpcMethod {
+        name: "build.errors",
+        description: "Get the errors from the most recent build",
+        params: any_object,
+        result: |gen| wrapped::<Vec<BuildError>>(gen, "errors"),
+    },
+    RpcMethod {
+        name: "test.run",
+        description: "Run the project tests",
+        params: schema::<TestRequest>,
+        result: |gen| wrapped::<TestResult>(gen, "result"),
+    },
+    RpcMethod {
+        name: "test.results",
+        description: "Get the result of the most recent test run",
+        params: any_object,
+        result: |gen| wrapped::<Option<TestResult>>(gen, "result"),
+    },
+    RpcMethod {
+        name: "docs.query",
+        description: "Search the Orbit documentation",
+        params: schema::<DocQueryRequest>,
+        result: |gen| wrapped::<Vec<DocItem>>(gen, "items"),
+    },
+    RpcMethod {
+        name: "docs.examples",
+        description: "Get Orbit code examples",
+        params: schema::<DocExampleRequest>,
+        result: |gen| wrapped::<Vec<DocExample>>(gen, "examples"),
+    },
+];
+
+/// A REST endpoint with the schemas of its body and response
+pub struct RestOperation {
+    /// HTTP method
+    pub method: &'static str,
+    /// Path, with parameters in braces
+    pub path: &'static str,
+    /// What the endpoint does
+    pub summary: &'static str,
+    /// Status code of a successful response
+    pub status: u16,
+    request: Option<SchemaFn>,
+    response: SchemaFn,
+}
+
+/// The REST endpoints under `/api`
+pub const REST_OPERATIONS: &[RestOperation] = &[
+    RestOperation {
+        method: "get",
+        path: "/api/status",
+        summary: "Get server status",
+        status: 200,
+        request: None,
+        response: any_object,
+    },
+    RestOperation {
+        method: "get",
+        path: "/api/docs",
+        summary: "Get API documentation",
+        status: 200,
+        request: None,
+        response: any_object,
+    },
+    RestOperation {
+        method: "get",
+        path: "/api/openapi.json",
+        summary: "Get this OpenAPI document",
+        status: 200,
+        request: None,
+        response: any_object,
+    },
+    RestOperation {
+        method: "get",
+        path: "/api/components",
+        summary: "List all components in the project",
+        status: 200,
+        request: None,
+        response: |gen| wrapped::<Vec<Component>>(gen, "components"),
+    },
+    RestOperation {
+        method: "post",
+        path: "/api/components",
+        summary: "Create a new component",
+        status: 201,
+        request: Some(schema::<CreateComponentRequest>),
+        response: |gen| wrapped::<Component>(gen, "component"),
+    },
+    RestOperation {
+        method: "get",
+        path: "/api/components/{id}",
+        summary: "Get details of a component",
+        status: 200,
+        request: None,
+        response: |gen| wrapped::<Component>(gen, "component"),
+    },
+    RestOperation {
+        method: "put",
+        path: "/api/components/{id}",
+        summary: "Update an existing component",
+        status: 200,
+        request: Some(schema::<UpdateComponentRequest>),
+        response: |gen| wrapped::<Component>(gen, "component"),
+    },
+    RestOperation {
+        method: "delete",
+        path: "/api/components/{id}",
+        summary: "Delete a component",
+        status: 200,
+        request: None,
+        response: deleted,
+    },
+    RestOperation {
+        method: "get",
+        path: "/api/project",
+        summary: "Get project information",
+        status: 200,
+        request: None,
+        response: |gen| wrapped::<Project>(gen, "project"),
+    },
+    RestOperation {
+        method: "post",
+        path: "/api/project/build",
+        summary: "Build the project",
+        status: 200,
+        request: Some(schema::<BuildRequest>),
+        response: |gen| wrapped::<BuildResult>(gen, "result"),
+    },
+    RestOperation {
+        method: "post",
+        path: "/api/project/test",
+        summary: "Run the project tests",
+        status: 200,
+        request: Some(schema::<TestRequest>),
+        response: |gen| wrapped::<TestResult>(gen, "result"),
+    },
+];
+
+/// Find a JSON-RPC method by name
+pub fn rpc_method(name: &str) -> Option<&'static RpcMethod> {
+    RPC_METHODS.iter().find(|method| method.name == name)
+}
+
+/// Self-contained JSON Schema for the params of a JSON-RPC method
+///
+/// The schema is always an object schema, which is what MCP expects of tool
+/// input schemas, and takes the optional `project` every method accepts.
+pub fn params_schema(method: &str) -> Value {
+    let mut gen = SchemaSettings::draft07()
+        .with(|settings| settings.inline_subschemas = true)
+        .into_generator();
+    let params = rpc_method(method).map_or(any_object as SchemaFn, |method| method.params);
+    let schema = params(&mut gen);
+
+    let mut schema = json!(schema);
+    if let Value::Object(schema) = &mut schema {
+        schema.insert("type".to_string(), json!("object"));
+        let properties = schema.entry("properties").or_insert_with(|| json!({}));
+        properties["project"] = json!({
+            "type": "string",
+            "description": "Project to work on: a root name, URI or path (default: the first root)"
+        });
+    }
+    schema
+}
+
+/// OpenAPI 3 document for the REST endpoints
+///
+/// The JSON-RPC methods are listed under `x-jsonrpc-methods` and share the
+/// same component schemas.
+pub fn openapi() -> Value {
+    let mut gen = SchemaSettings::openapi3().into_generator();
+
+    let mut paths = Map::new();
+    for operation in REST_OPERATIONS {
+        let mut spec = json!({
+            "summary": operation.summary,
+            "responses": {
+                operation.status.to_string(): {
+                    "description": "Success",
+                    "content": {
+                        "application/json": { "schema": (operation.response)(&mut gen) }
+                    }
+                },
+                "default": {
+                    "description": "Error",
+                    "content": {
+                        "application/json": { "schema": error_body(&mut gen) }
+                    }
+                }
+            }
+        });
+
+        let parameters: Vec<Value> = path_parameters(operation.path)
+            .map(|name| {
+                json!({
+                    "name": name,
+                    "in": "path",
+                    "required": true,
+                    "schema": { "type": "string" }
+                })
+            })
+            .collect();
+        if !parameters.is_empty() {
+            spec["parameters"] = json!(parameters);
+        }
+
+        if let Some(request) = operation.request {
+            spec["requestBody"] = json!({
+                "required": true,
+                "content": {
+                    "application/json": { "schema": request(&mut gen) }
+                }
+            });
+        }
+
+        paths
+            .entry(operation.path)
+            .or_insert_with(|| json!({}))
+            .as_object_mut()
+            .map(|path| path.insert(operation.method.to_string(), spec));
+    }
+
+    let methods: Map<String, Value> = RPC_METHODS
+        .iter()
+        .map(|method| {
+            (
+                method.name.to_string(),
+                json!({
+                    "description": method.description,
+                    "params": (method.params)(&mut gen),
+                    "result": (method.result)(&mut gen),
+                }),
+            )
+        })
+        .collect();
+
+    json!({
+        "openapi": OPENAPI_VERSION,
+        "info": {
+            "title": "Orbit MCP Server API",
+            "description": "API for the Orbit MCP Server",
+            "version": env!("CARGO_PKG_VERSION"),
+        },
+        "paths": paths,
+        "components": {
+            "schemas": gen.take_definitions(),
+        },
+        "x-jsonrpc-methods": methods,
+    })
+}
+
+// Names of the `{parameters}` in a path
+fn path_parameters(path: &str) -> impl Iterator<Item = &str> {
+    path.split('/')
+        .filter_map(|segment| segment.strip_prefix('{')?.strip_suffix('}'))
+}
+
+// Schema of a model type
+fn schema<T: JsonSchema>(gen: &mut SchemaGenerator) -> Schema {
+    gen.subschema_for::<T>()
+}
+
+// Schema of an object with a single required field holding `T`
+fn wrapped<T: JsonSchema>(gen: &mut SchemaGenerator, field: &str) -> Schema {
+    let mut schema = object();
+    let properties = schema.object();
+    properties
+        .properties
+        .insert(field.to_string(), gen.subschema_for::<T>());
+    properties.required.insert(field.to_string());
+    schema.into()
+}
+
+// Schema of a delete confirmation
+fn deleted(gen: &mut SchemaGenerator) -> Schema {
+    let mut schema = object();
+    let properties = schema.object();
+    properties
+        .properties
+        .insert("success".to_string(), gen.subschema_for::<bool>());
+    properties
+        .properties
+        .insert("message".to_string(), gen.subschema_for::<String>());
+    properties.required.insert("success".to_string());
+    properties.required.insert("message".to_string());
+    schema.into()
+}
+
+// Schema of an error response: the error message, or a JSON-RPC error
+// response when the request was refused before reaching its handler, such
+// as a 401 for a missing token
+fn error_body(gen: &mut SchemaGenerator) -> Schema {
+    let mut rpc_error = object();
+    let properties = rpc_error.object();
+    properties
+        .properties
+        .insert("jsonrpc".to_string(), gen.subschema_for::<String>());
+    properties
+        .properties
+        .insert("id".to_string(), gen.subschema_for::<Value>());
+    properties
+        .properties
+        .insert("error".to_string(), gen.subschema_for::<JsonRpcError>());
+    for field in ["jsonrpc", "id", "error"] {
+        properties.required.insert(field.to_string());
+    }
+
+    let mut schema = SchemaObject::default();
+    schema.subschemas().one_of = Some(vec![wrapped::<String>(gen, "error"), rpc_error.into()]);
+    schema.into()
+}
+
+// Schema of a free-form object
+fn any_object(_gen: &mut SchemaGenerator) -> Schema {
+    object().into()
+}
+
+// Object schema to fill in
+fn object() -> SchemaObject {
+    SchemaObject {
+        instance_type: Some(InstanceType::Object.into()),
+        ..Default::default()
+    }
+}
@@ -1,11 +1,12 @@
 // Testing-related API models
 
+use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::path::PathBuf;
 use std::time::Duration;
 
 /// Test options
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TestOptions {
     /// Test filter
     pub filter: Option<String>,
@@ -18,7 +19,7 @@ pub struct TestOptions {
 }
 
 /// Test result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TestResult {
     /// Number of tests run
     pub tests_run: u32,
@@ -30,6 +31,7 @@ pub struct TestResult {
     pub tests_ignored: u32,
     /// Total time taken
     #[serde(with = "humantime_serde")]
+    #[schemars(with = "String")]
     pub duration: Duration,
     /// Test failures
     pub failures: Vec<TestFailure>,
@@ -38,7 +40,7 @@ pub struct TestResult {
 }
 
 /// Test failure
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TestFailure {
     /// Test name
     pub test_name: String,
@@ -51,7 +53,7 @@ pub struct TestFailure {
 }
 
 /// Test location
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TestLocation {
     /// File path
     pub file: PathBuf,
@@ -62,7 +64,7 @@ pub struct TestLocation {
 }
 
 /// Test coverage data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TestCoverage {
     /// Line coverage percentage
     pub line_coverage: f32,
@@ -75,7 +77,7 @@ pub struct TestCoverage {
 }
 
 /// File coverage data
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct FileCoverage {
     /// File path
     pub file: PathBuf,
@@ -88,7 +90,7 @@ pub struct FileCoverage {
 }
 
 /// Test request
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
 pub struct TestRequest {
     /// Test options
     pub options: TestOptions,
@@ -0,0 +1,482 @@
+// MCP client implementation
+
+pub mod transport;
+
+pub use transport::ConnectOptions;
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use futures_util::{stream, Stream};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use thiserror::Error;
+use tokio::process::Command;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::{broadcast, mpsc, oneshot};
+
+use crate::api::build::{BuildError, BuildOptions, BuildRequest, BuildResult};
+use crate::api::clients::{ClientHelloRequest, ClientHelloResult};
+use crate::api::components::{
+    Component, ComponentAnalysis, ComponentIdRequest, CreateComponentRequest,
+    UpdateComponentByIdRequest, UpdateComponentRequest,
+};
+use crate::api::documentation::{DocExample, DocExampleRequest, DocItem, DocQueryRequest};
+use crate::api::events::{SubscribeEventsRequest, SubscribeEventsResult, UnsubscribeEventsRequest};
+use crate::api::jsonrpc::{JsonRpcError, JsonRpcNotification, JsonRpcResponse, JSONRPC_VERSION};
+use crate::api::logging::{LoggingLevel, SetLevelRequest};
+use crate::api::mcp::{
+    CallToolRequest, CallToolResult, Implementation, InitializeRequest, InitializeResult,
+    ListToolsResult, LATEST_PROTOCOL_VERSION,
+};
+use crate::api::projects::Project;
+use crate::api::prompts::{GetPromptRequest, GetPromptResult, ListPromptsResult};
+use crate::api::resources::{ListResourcesResult, ReadResourceResult, ResourceUriRequest};
+use crate::api::testing::{TestOptions, TestRequest, TestResult};
+use crate::server::ServerEvent;
+
+/// Number of notifications a slow subscriber may fall behind before missing some
+const NOTIFICATION_CAPACITY: usize = 256;
+
+/// Errors returned by [`McpClient`]
+#[derive(Error, Debug)]
+pub enum ClientError {
+    #[error("Server error {}: {}", .0.code, .0.message)]
+    Rpc(JsonRpcError),
+
+    #[error("Transport error: {0}")]
+    Transport(String),
+
+    #[error("Connection closed")]
+    Closed,
+
+    #[error("Invalid response: {0}")]
+    InvalidResponse(#[from] serde_json::Error),
+
+    #[error("IO error: {0}")]
+    IoError(#[from] std::io::Error),
+}
+
+// Replies still awaited, by request ID
+type Pending = Arc<Mutex<HashMap<String, oneshot::Sender<JsonRpcResponse>>>>;
+
+// A reply awaited by a call, no longer awaited once the call is dropped
+struct AwaitedReply {
+    pending: Pending,
+    id: String,
+}
+
+impl Drop for AwaitedReply {
+    fn drop(&mut self) {
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.remove(&self.id);
+        }
+    }
+}
+
+/// Typed client for an orbit-mcp server
+///
+/// Replies are matched to requests by ID, so calls can run concurrently from
+/// clones of the same client. Dropping the last clone closes the connection.
+#[derive(Clone)]
+pub struct McpClient {
+    /// Messages to send to the server
+    outgoing: mpsc::UnboundedSender<Value>,
+    /// Requests waiting for a reply
+    pending: Pending,
+    /// Notifications received from the server
+    notifications: broadcast::Sender<JsonRpcNotification>,
+    /// ID of the next request
+    next_id: Arc<AtomicU64>,
+}
+
+impl McpClient {
+    /// Connect to the WebSocket endpoint, such as `ws://localhost:3000/api/ws`
+    pub async fn connect_websocket(url: &str) -> Result<Self, ClientError> {
+        Self::connect_websocket_with(url, &ConnectOptions::default()).await
+    }
+
+    /// Connect to the WebSocket endpoint of a server that requires a token
+    pub async fn connect_websocket_with_token(url: &str, token: &str) -> Result<Self, ClientError> {
+        Self::connect_websocket_with(url, &ConnectOptions::default().with_token(token)).await
+    }
+
+    /// Connect to the WebSocket endpoint with a token, a pinned certificate
+    /// fingerprint or both
+    pub async fn connect_websocket_with(
+        url: &str,
+        options: &ConnectOptions,
+    ) -> Result<Self, ClientError> {
+        Ok(Self::from_channels(
+            transport::websocket(url, options).await?,
+        ))
+    }
+
+    /// Spawn a server with `--transport stdio` and talk to it over its stdin/stdout
+    pub fn connect_stdio(command: Command) -> Result<Self, ClientError> {
+        Ok(Self::from_channels(transport::stdio(command)?))
+    }
+
+    /// Use the Streamable HTTP endpoint, such as `http://localhost:3000/api/mcp`
+    ///
+    /// The first call must be [`McpClient::initialize`], which starts the
+    /// session; calls made meanwhile wait for it.
+    pub fn connect_http(url: &str) -> Result<Self, ClientError> {
+        Self::connect_http_with(url, &ConnectOptions::default())
+    }
+
+    /// Use the Streamable HTTP endpoint of a server that requires a token
+    pub fn connect_http_with_token(url: &str, token: &str) -> Result<Self, ClientError> {
+        Self::connect_http_with(url, &ConnectOptions::default().with_token(token))
+    }
+
+    /// Use the Streamable HTTP endpoint with a token, a pinned certificate
+    /// fingerprint or both
+    pub fn connect_http_with(url: &str, options: &ConnectOptions) -> Result<Self, ClientError> {
+        Ok(Self::from_channels(transport::http(url, options)?))
+    }
+
+    /// Build a client on top of channels to and from any transport
+    pub fn from_channels((outgoing, mut incoming): transport::Channels) -> Self {
+        let pending: Pending = Arc::default();
+        let (notifications, _) = broadcast::channel(NOTIFICATION_CAPACITY);
+
+        // A weak handle, so dropping the client still closes the connection
+        let replies = outgoing.downgrade();
+        let client = Self {
+            outgoing,
+            pending: pending.clone(),
+            notifications: notifications.clone(),
+            next_id: Arc::new(AtomicU64::new(1)),
+        };
+
+        // Route replies to their callers and broadcast everything else
+        tokio::spawn(async move {
+            while let Some(message) = incoming.recv().await {
+                let has_method = message.get("method").is_some();
+                match message.get("id") {
+                    // The server asked us something we can't answer
+                    Some(id) if has_method => {
+                        let method = message["method"].as_str().unwrap_or_default();
+                        let response = JsonRpcResponse::failure(
+                            id.clone(),
+                            JsonRpcError::method_not_found(method),
+                        );
+                        if let Some(replies) = replies.upgrade() {
+                            let _ = replies.send(json!(response));
+                        }
+                    }
+                    Some(id) => {
+                        let waiter = pending
+                            .lock()
+                            .ok()
+                            .and_then(|mut pending| pending.remove(&id.to_string()));
+                        if let (Some(waiter), Ok(response)) =
+                            (waiter, serde_json::from_value(message))
+                        {
+                            let _ = waiter.send(response);
+                        }
+                    }
+                    None if has_method => {
+                        if let Ok(notification) = serde_json::from_value(message) {
+                            let _ = notifications.send(notification);
+                        }
+                    }
+                    None => {}
+                }
+            }
+
+            // Fail whatever is still waiting
+            if let Ok(mut pending) = pending.lock() {
+                pending.clear();
+            }
+        });
+
+        client
+    }
+
+    /// Send a request and decode its result
+    pub async fn request<P, T>(&self, method: &str, params: P) -> Result<T, ClientError>
+    where
+        P: Serialize,
+        T: DeserializeOwned,
+    {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed);
+        let (tx, rx) = oneshot::channel();
+        let awaited = AwaitedReply {
+            pending: self.pending.clone(),
+            id: json!(id).to_string(),
+        };
+        if let Ok(mut pending) = self.pending.lock() {
+            pending.insert(awaited.id.clone(), tx);
+        }
+
+        let request = json!({
+            "jsonrpc": JSONRPC_VERSION,
+            "id": id,
+            "method": method,
+            "params": params,
+        });
+        if self.outgoing.send(request).is_err() {
+            return Err(ClientError::Closed);
+        }
+
+        let response = rx.await.map_err(|_| ClientError::Closed)?;
+        if let Some(error) = response.error {
+            return Err(ClientError::Rpc(error));
+        }
+        Ok(serde_json::from_value(
+            response.result.unwrap_or(Value::Null),
+        )?)
+    }
+
+    /// Send a notification, which gets no reply
+    pub fn notify<P: Serialize>(&self, method: &str, params: P) -> Result<(), ClientError> {
+        let notification = json!({
+            "jsonrpc": JSONRPC_VERSION,
+            "method": method,
+            "params": params,
+        });
+        self.outgoing
+            .send(notification)
+            .map_err(|_| ClientError::Closed)
+    }
+
+    /// Notifications from the server, from now on
+    pub fn notifications(&self) -> impl Stream<Item = JsonRpcNotification> {
+        stream::unfold(self.notifications.subscribe(), |mut rx| async move {
+            loop {
+                match rx.recv().await {
+                    Ok(notification) => return Some((notification, rx)),
+                    Err(RecvError::Lagged(_)) => continue,
+                    Err(RecvError::Closed) => return None,
+                }
+            }
+        })
+    }
+
+    /// Server events, from now on
+    pub fn events(&self) -> impl Stream<Item = ServerEvent> {
+        futures_util::StreamExt::filter_map(self.notifications(), |notification| async move {
+            ServerEvent::from_notification(&notification)
+        })
+    }
+
+    /// Start an MCP session
+    ///
+    /// Declare `experimental.events` in `capabilities` to keep receiving
+    /// server events afterwards.
+    pub async fn initialize(
+        &self,
+        client_info: Implementation,
+        capabilities: Value,
+    ) -> Result<InitializeResult, ClientError> {
+        let request = InitializeRequest {
+            protocol_version: LATEST_PROTOCOL_VERSION.to_string(),
+            capabilities,
+            client_info,
+        };
+        let result = self.request("initialize", request).await?;
+        self.notify("notifications/initialized", json!({}))?;
+        Ok(result)
+    }
+
+    /// Introduce the client without the MCP lifecycle
+    pub async fn hello(
+        &self,
+        request: ClientHelloRequest,
+    ) -> Result<ClientHelloResult, ClientError> {
+        self.request("client.hello", request).await
+    }
+
+    /// Check that the server is responding
+    pub async fn ping(&self) -> Result<(), ClientError> {
+        self.request::<_, Value>("ping", json!({}))
+            .await
+            .map(|_| ())
+    }
+
+    /// List the project's components
+    pub async fn list_components(&self) -> Result<Vec<Component>, ClientError> {
+        self.request_field("component.list", json!({}), "components")
+            .await
+    }
+
+    /// Get a component by ID
+    pub async fn get_component(&self, id: &str) -> Result<Component, ClientError> {
+        let request = ComponentIdRequest { id: id.to_string() };
+        self.request_field("component.get", request, "component")
+            .await
+    }
+
+    /// Create a component
+    pub async fn create_component(
+        &self,
+        request: CreateComponentRequest,
+    ) -> Result<Component, ClientError> {
+        self.request_field("component.create", request, "component")
+            .await
+    }
+
+    /// Update a component
+    pub async fn update_component(
+        &self,
+        id: &str,
+        update: UpdateComponentRequest,
+    ) -> Result<Component, ClientError> {
+        let request = UpdateComponentByIdRequest {
+            id: id.to_string(),
+            update,
+        };
+        self.request_field("component.update", request, "component")
+            .await
+    }
+
+    /// Delete a component
+    pub async fn delete_component(&self, id: &str) -> Result<(), ClientError> {
+        let request = ComponentIdRequest { id: id.to_string() };
+        self.request::<_, Value>("component.delete", request)
+            .await
+            .map(|_| ())
+    }
+
+    /// Analyze a component
+    pub async fn analyze_component(&self, id: &str) -> Result<ComponentAnalysis, ClientError> {
+        let request = ComponentIdRequest { id: id.to_string() };
+        self.request_field("component.analyze", request, "analysis")
+            .await
+    }
+
+    /// Get information about the project
+    pub async fn project_info(&self) -> Result<Project, ClientError> {
+        self.request_field("project.info", json!({}), "project")
+            .await
+    }
+
+    /// Build the project
+    pub async fn build(&self, options: BuildOptions) -> Result<BuildResult, ClientError> {
+        self.request_field("build.compile", BuildRequest { options }, "result")
+            .await
+    }
+
+    /// Get the errors of the most recent build
+    pub async fn build_errors(&self) -> Result<Vec<BuildError>, ClientError> {
+        self.request_field("build.errors", json!({}), "errors")
+            .await
+    }
+
+    /// Run the project tests
+    pub async fn run_tests(&self, options: TestOptions) -> Result<TestResult, ClientError> {
+        self.request_field("test.run", TestRequest { options }, "result")
+            .await
+    }
+
+    /// Get the result of the most recent test run
+    pub async fn test_results(&self) -> Result<Option<TestResult>, ClientError> {
+        self.request_field("test.results", json!({}), "result")
+            .await
+    }
+
+    /// Search the documentation
+    pub async fn query_docs(&self, request: DocQueryRequest) -> Result<Vec<DocItem>, ClientError> {
+        self.request_field("docs.query", request, "items").await
+    }
+
+    /// Get documentation examples
+    pub async fn doc_examples(
+        &self,
+        request: DocExampleRequest,
+    ) -> Result<Vec<DocExample>, ClientError> {
+        self.request_field("docs.examples", request, "examples")
+            .await
+    }
+
+    /// Subscribe to matching server events, returning the subscription ID
+    pub async fn subscribe_events(
+        &self,
+        request: SubscribeEventsRequest,
+    ) -> Result<String, ClientError> {
+        let result: SubscribeEventsResult = self.request("events.subscribe", request).await?;
+        Ok(result.subscription_id)
+    }
+
+    /// Cancel one event subscription, or all of them
+    pub async fn unsubscribe_events(
+        &self,
+        subscription_id: Option<String>,
+    ) -> Result<(), ClientError> {
+        let request = UnsubscribeEventsRequest { subscription_id };
+        self.request::<_, Value>("events.unsubscribe", request)
+            .await
+            .map(|_| ())
+    }
+
+    /// Ask the server to send log messages at or above `level` as
+    /// `notifications/message`
+    pub async fn set_log_level(&self, level: LoggingLevel) -> Result<(), ClientError> {
+        self.request::<_, Value>("logging/setLevel", SetLevelRequest { level })
+            .await
+            .map(|_| ())
+    }
+
+    /// List the MCP tools
+    pub async fn list_tools(&self) -> Result<ListToolsResult, ClientError> {
+        self.request("tools/list", json!({})).await
+    }
+
+    /// Call an MCP tool
+    pub async fn call_tool(
+        &self,
+        name: &str,
+        arguments: Value,
+    ) -> Result<CallToolResult, ClientError> {
+        let request = CallToolRequest {
+            name: name.to_string(),
+            arguments: Some(arguments),
+        };
+        self.request("tools/call", request).await
+    }
+
+    /// List the MCP resources
+    pub async fn list_resources(&self) -> Result<ListResourcesResult, ClientError> {
+        self.request("resources/list", json!({})).await
+    }
+
+    /// Read an MCP resource
+    pub async fn read_resource(&self, uri: &str) -> Result<ReadResourceResult, ClientError> {
+        let request = ResourceUriRequest {
+            uri: uri.to_string(),
+        };
+        self.request("resources/read", request).await
+    }
+
+    /// List the MCP prompts
+    pub async fn list_prompts(&self) -> Result<ListPromptsResult, ClientError> {
+        self.request("prompts/list", json!({})).await
+    }
+
+    /// Render an MCP prompt
+    pub async fn get_prompt(
+        &self,
+        request: GetPromptRequest,
+    ) -> Result<GetPromptResult, ClientError> {
+        self.request("prompts/get", request).await
+    }
+
+    // Send a request and decode one field of its result
+    async fn request_field<P, T>(
+        &self,
+        method: &str,
+        params: P,
+        field: &str,
+    ) -> Result<T, ClientError>
+    where
+        P: Serialize,
+        T: DeserializeOwned,
+    {
+        let mut result: Value = self.request(method, params).await?;
+        Ok(serde_json::from_value(result[field].take())?)
+    }
+}
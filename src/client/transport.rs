@@ -0,0 +1,515 @@
+// Client transports: each one turns a connection into a pair of JSON channels
+
+use std::sync::Arc;
+use std::time::SystemTime;
+
+use futures_util::{SinkExt, StreamExt};
+use hyper::body::HttpBody;
+use hyper::client::HttpConnector;
+use hyper::header::HeaderValue;
+use hyper::{header, Body, Method, Request, StatusCode};
+use hyper_rustls::HttpsConnector;
+use log::{debug, warn};
+use rustls::client::{ServerCertVerified, ServerCertVerifier};
+use rustls::{Certificate, ClientConfig, RootCertStore, ServerName};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::process::Command;
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::{connect_async_tls_with_config, tungstenite::protocol::Message, Connector};
+
+use crate::api::jsonrpc::{JsonRpcError, JsonRpcResponse, SERVER_ERROR};
+use crate::client::ClientError;
+use crate::transport::streamable_http::SESSION_HEADER;
+use crate::utils::tls;
+
+/// Messages to send to the server, and messages received from it
+pub type Channels = (mpsc::UnboundedSender<Value>, mpsc::UnboundedReceiver<Value>);
+
+// HTTP client speaking both `http://` and `https://`
+type HttpClient = hyper::Client<HttpsConnector<HttpConnector>>;
+
+/// How to reach a server over WebSocket or Streamable HTTP
+#[derive(Debug, Clone, Default)]
+pub struct ConnectOptions {
+    /// Bearer token to present, if the server requires one
+    pub token: Option<String>,
+    /// SHA-256 fingerprint the server's certificate must have, such as the
+    /// `tls_fingerprint` in `/api/status`
+    ///
+    /// The pinned certificate is trusted instead of the system's certificate
+    /// authorities, which lets clients reach a server with a self-signed
+    /// certificate. Needs a `wss://` or `https://` URL.
+    pub fingerprint: Option<String>,
+}
+
+impl ConnectOptions {
+    /// Present a bearer token
+    pub fn with_token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Only accept a server certificate with this SHA-256 fingerprint
+    pub fn with_fingerprint(mut self, fingerprint: impl Into<String>) -> Self {
+        self.fingerprint = Some(fingerprint.into());
+        self
+    }
+
+    // The `Authorization` header to send, if any
+    fn authorization(&self) -> Option<String> {
+        self.token.as_ref().map(|token| format!("Bearer {}", token))
+    }
+
+    // TLS settings checking the server certificate against the pinned
+    // fingerprint, or else against the system's certificate authorities
+    fn tls_config(&self, secure: bool) -> Result<Arc<ClientConfig>, ClientError> {
+        let builder = ClientConfig::builder().with_safe_defaults();
+        let config = match &self.fingerprint {
+            Some(_) if !secure => {
+                return Err(ClientError::Transport(
+                    "A pinned certificate fingerprint needs a wss:// or https:// URL".to_string(),
+                ))
+            }
+            Some(fingerprint) => builder
+                .with_custom_certificate_verifier(Arc::new(PinnedCertificate {
+                    fingerprint: fingerprint.clone(),
+                }))
+                .with_no_client_auth(),
+            None => {
+                let mut roots = RootCertStore::empty();
+                if secure {
+                    let certs = rustls_native_certs::load_native_certs()?;
+                    let certs: Vec<Vec<u8>> = certs.into_iter().map(|cert| cert.0).collect();
+                    roots.add_parsable_certificates(&certs);
+                }
+                builder.with_root_certificates(roots).with_no_client_auth()
+            }
+        };
+        Ok(Arc::new(config))
+    }
+}
+
+// Accepts exactly the server certificate with the pinned fingerprint
+struct PinnedCertificate {
+    fingerprint: String,
+}
+
+impl ServerCertVerifier for PinnedCertificate {
+    fn verify_server_cert(
+        &self,
+        end_entity: &Certificate,
+        _intermediates: &[Certificate],
+        _server_name: &ServerName,
+        _scts: &mut dyn Iterator<Item = &[u8]>,
+        _ocsp_response: &[u8],
+        _now: SystemTime,
+    ) -> Result<ServerCertVerified, rustls::Error> {
+        let fingerprint = tls::der_fingerprint(&end_entity.0);
+        if tls::same_fingerprint(&fingerprint, &self.fingerprint) {
+            Ok(ServerCertVerified::assertion())
+        } else {
+            Err(rustls::Error::General(format!(
+                "Server certificate fingerprint {} doesn't match the pinned one",
+                fingerprint
+            )))
+        }
+    }
+}
+
+/// Connect to the WebSocket endpoint, such as `ws://localhost:3000/api/ws`
+/// or `wss://...` on a server with TLS
+pub async fn websocket(url: &str, options: &ConnectOptions) -> Result<Channels, ClientError> {
+    let mut request = url
+        .into_client_request()
+        .map_err(|e| ClientError::Transport(e.to_string()))?;
+    if let Some(authorization) = options.authorization() {
+        let value = HeaderValue::from_str(&authorization)
+            .map_err(|e| ClientError::Transport(e.to_string()))?;
+        request.headers_mut().insert(header::AUTHORIZATION, value);
+    }
+    let secure = request.uri().scheme_str() == Some("wss");
+    let connector = Connector::Rustls(options.tls_config(secure)?);
+
+    let (ws_stream, _) = connect_async_tls_with_config(request, None, false, Some(connector))
+        .await
+        .map_err(|e| ClientError::Transport(e.to_string()))?;
+    let (mut sink, mut stream) = ws_stream.split();
+
+    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<Value>();
+    let (incoming_tx, incoming_rx) = mpsc::unbounded_channel::<Value>();
+
+    tokio::spawn(async move {
+        while let Some(message) = outgoing_rx.recv().await {
+            if sink.send(Message::Text(message.to_string())).await.is_err() {
+                break;
+            }
+        }
+        let _ = sink.close().await;
+    });
+
+    tokio::spawn(async move {
+        while let Some(Ok(frame)) = stream.next().await {
+            match frame {
+                Message::Text(text) => forward(&incoming_tx, &text),
+                Message::Close(_) => break,
+                _ => {} // Pings are answered by tungstenite
+            }
+        }
+    });
+
+    Ok((outgoing_tx, incoming_rx))
+}
+
+/// Spawn a server process, such as `orbit-mcp --transport stdio`, and talk to
+/// it over its stdin/stdout
+///
+/// The process is killed when the connection is dropped.
+pub fn stdio(mut command: Command) -> Result<Channels, ClientError> {
+    let mut child = command
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()?;
+
+    let (Some(mut stdin), Some(stdout)) = (child.stdin.take(), child.stdout.take()) else {
+        return Err(ClientError::Transport(
+            "Server process has no stdin/stdout".to_string(),
+        ));
+    };
+
+    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<Value>();
+    let (incoming_tx, incoming_rx) = mpsc::unbounded_channel::<Value>();
+
+    tokio::spawn(async move {
+        while let Some(message) = outgoing_rx.recv().await {
+            let line = format!("{}\n", message);
+            if stdin.write_all(line.as_bytes()).await.is_err() || stdin.flush().await.is_err() {
+                break;
+            }
+        }
+    });
+
+    tokio::spawn(async move {
+        let mut lines = BufReader::new(stdout).lines();
+        while let Ok(Some(line)) = lines.next_line().await {
+            forward(&incoming_tx, &line);
+        }
+
+        // Owning the child here keeps the process alive as long as it talks to us
+        let _ = child.kill().await;
+    });
+
+    Ok((outgoing_tx, incoming_rx))
+}
+
+/// Talk to the Streamable HTTP endpoint, such as `http://localhost:3000/api/mcp`
+/// or `https://...` on a server with TLS
+///
+/// Each message is POSTed on its own. The session starts with the reply to
+/// `initialize`, after which notifications are read from an SSE stream.
+/// Messages sent before then wait for the session, so they carry its ID.
+/// Dropping the connection ends the session.
+pub fn http(url: &str, options: &ConnectOptions) -> Result<Channels, ClientError> {
+    let url: hyper::Uri = url
+        .parse()
+        .map_err(|e: hyper::http::uri::InvalidUri| ClientError::Transport(e.to_string()))?;
+    let secure = url.scheme_str() == Some("https");
+    let connector = hyper_rustls::HttpsConnectorBuilder::new()
+        .with_tls_config((*options.tls_config(secure)?).clone())
+        .https_or_http()
+        .enable_http1()
+        .build();
+    let client: HttpClient = hyper::Client::builder().build(connector);
+
+    let (outgoing_tx, mut outgoing_rx) = mpsc::unbounded_channel::<Value>();
+    let (incoming_tx, incoming_rx) = mpsc::unbounded_channel::<Value>();
+    let authorization = options.authorization();
+
+    tokio::spawn(async move {
+        let mut session_id: Option<String> = None;
+
+        while let Some(message) = outgoing_rx.recv().await {
+            match &session_id {
+                // Requests run concurrently, like on the other transports
+                Some(session_id) => {
+                    let (client, url, incoming_tx) =
+                        (client.clone(), url.clone(), incoming_tx.clone());
+                    let (authorization, session_id) = (authorization.clone(), session_id.clone());
+                    tokio::spawn(async move {
+                        send(
+                            &client,
+                            &url,
+                            authorization.as_deref(),
+                            Some(&session_id),
+                            &message,
+                            &incoming_tx,
+                        )
+                        .await;
+                    });
+                }
+                // Until the session starts, later messages queue up behind this one
+                None => {
+                    session_id = send(
+                        &client,
+                        &url,
+                        authorization.as_deref(),
+                        None,
+                        &message,
+                        &incoming_tx,
+                    )
+                    .await;
+                    if let Some(session_id) = &session_id {
+                        tokio::spawn(notification_stream(
+                            client.clone(),
+                            url.clone(),
+                            authorization.clone(),
+                            session_id.clone(),
+                            incoming_tx.clone(),
+                        ));
+                    }
+                }
+            }
+        }
+
+        // Let the server forget the session rather than wait for it to expire
+        if let Some(session_id) = session_id {
+            if let Err(e) = end_session(&client, &url, authorization.as_deref(), &session_id).await
+            {
+                warn!("Failed to end session {}: {}", session_id, e);
+            }
+        }
+    });
+
+    Ok((outgoing_tx, incoming_rx))
+}
+
+// POST one message and pass its reply on, returning the session it started
+// (if any)
+async fn send(
+    client: &HttpClient,
+    url: &hyper::Uri,
+    authorization: Option<&str>,
+    session_id: Option<&str>,
+    message: &Value,
+    incoming_tx: &mpsc::UnboundedSender<Value>,
+) -> Option<String> {
+    match post(client, url, authorization, session_id, message, incoming_tx).await {
+        Ok((new_session, reply)) => {
+            // A failed `initialize` starts no session
+            let failed = reply
+                .as_ref()
+                .is_some_and(|reply| reply.get("error").is_some());
+            if let Some(reply) = reply {
+                forward_value(incoming_tx, reply);
+            }
+            new_session.filter(|_| session_id.is_none() && !failed)
+        }
+        Err(error) => {
+            // Fail the request rather than leave it waiting
+            if let Some(id) = message
+                .get("id")
+                .filter(|_| message.get("method").is_some())
+            {
+                let response = JsonRpcResponse::failure(
+                    id.clone(),
+                    JsonRpcError::new(SERVER_ERROR, error.to_string()),
+                );
+                forward_value(incoming_tx, json!(response));
+            }
+            None
+        }
+    }
+}
+
+// POST one message, returning the session header and the reply (if any)
+//
+// Replies may come as JSON or over an SSE stream, which carries the
+// server's notifications and requests about the message before the reply.
+// Those are passed on as they arrive.
+async fn post(
+    client: &HttpClient,
+    url: &hyper::Uri,
+    authorization: Option<&str>,
+    session_id: Option<&str>,
+    message: &Value,
+    incoming_tx: &mpsc::UnboundedSender<Value>,
+) -> Result<(Option<String>, Option<Value>), ClientError> {
+    let mut request = Request::builder()
+        .method(Method::POST)
+        .uri(url.clone())
+        .header(header::CONTENT_TYPE, "application/json")
+        .header(header::ACCEPT, "application/json, text/event-stream");
+    if let Some(authorization) = authorization {
+        request = request.header(header::AUTHORIZATION, authorization);
+    }
+    if let Some(session_id) = session_id {
+        request = request.header(SESSION_HEADER, session_id);
+    }
+    let request = request
+        .body(Body::from(message.to_string()))
+        .map_err(|e| ClientError::Transport(e.to_string()))?;
+
+    let response = client
+        .request(request)
+        .await
+        .map_err(|e| ClientError::Transport(e.to_string()))?;
+    let status = response.status();
+    let session = response
+        .headers()
+        .get(SESSION_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string);
+    let is_event_stream = response
+        .headers()
+        .get(header::CONTENT_TYPE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.starts_with("text/event-stream"));
+
+    if status.is_success() && is_event_stream {
+        let mut reply = None;
+        read_events(response.into_body(), |text| {
+            match serde_json::from_str::<Value>(text) {
+                Ok(message) if message.get("method").is_none() => reply = Some(message),
+                Ok(message) => forward_value(incoming_tx, message),
+                Err(e) => warn!("Ignoring malformed message from server: {}", e),
+            }
+        })
+        .await;
+        return Ok((session, reply));
+    }
+
+    let body = hyper::body::to_bytes(response.into_body())
+        .await
+        .map_err(|e| ClientError::Transport(e.to_string()))?;
+
+    if status == StatusCode::ACCEPTED {
+        return Ok((session, None));
+    }
+
+    // Parse errors and such still come with a JSON-RPC body
+    match serde_json::from_slice(&body) {
+        Ok(reply) => Ok((session, Some(reply))),
+        Err(_) => Err(ClientError::Transport(format!("HTTP {}", status))),
+    }
+}
+
+// Read the session's SSE stream and forward every message on it
+async fn notification_stream(
+    client: HttpClient,
+    url: hyper::Uri,
+    authorization: Option<String>,
+    session_id: String,
+    incoming_tx: mpsc::UnboundedSender<Value>,
+) {
+    let mut request = Request::builder()
+        .method(Method::GET)
+        .uri(url)
+        .header(header::ACCEPT, "text/event-stream")
+        .header(SESSION_HEADER, session_id);
+    if let Some(authorization) = authorization {
+        request = request.header(header::AUTHORIZATION, authorization);
+    }
+    let request = request.body(Body::empty());
+    let response = match request {
+        Ok(request) => client.request(request).await,
+        Err(e) => {
+            warn!("Failed to build notification stream request: {}", e);
+            return;
+        }
+    };
+    let body = match response {
+        Ok(response) if response.status().is_success() => response.into_body(),
+        Ok(response) => {
+            warn!("Notification stream refused: HTTP {}", response.status());
+            return;
+        }
+        Err(e) => {
+            warn!("Failed to open notification stream: {}", e);
+            return;
+        }
+    };
+
+    read_events(body, |text| forward(&incoming_tx, text)).await;
+
+    debug!("Notification stream closed");
+}
+
+// Read an SSE stream, handing the data of each event on as it completes
+async fn read_events(mut body: Body, mut on_event: impl FnMut(&str)) {
+    let mut buffer = Vec::new();
+    let mut data = Vec::new();
+    while let Some(Ok(chunk)) = body.data().await {
+        buffer.extend_from_slice(&chunk);
+
+        // Only whole lines are decoded, so characters split across chunks
+        // stay intact
+        while let Some(end) = buffer.iter().position(|&byte| byte == b'\n') {
+            let line: Vec<u8> = buffer.drain(..=end).collect();
+            let line = String::from_utf8_lossy(&line);
+            let line = line.trim_end_matches(['\n', '\r']);
+
+            // Events are separated by blank lines; only their data lines
+            // matter here
+            if line.is_empty() {
+                if !data.is_empty() {
+                    on_event(&data.join("\n"));
+                    data.clear();
+                }
+            } else if let Some(value) = line.strip_prefix("data:") {
+                data.push(value.strip_prefix(' ').unwrap_or(value).to_string());
+            }
+        }
+    }
+}
+
+// End a session with DELETE
+async fn end_session(
+    client: &HttpClient,
+    url: &hyper::Uri,
+    authorization: Option<&str>,
+    session_id: &str,
+) -> Result<(), ClientError> {
+    let mut request = Request::builder()
+        .method(Method::DELETE)
+        .uri(url.clone())
+        .header(SESSION_HEADER, session_id);
+    if let Some(authorization) = authorization {
+        request = request.header(header::AUTHORIZATION, authorization);
+    }
+    let request = request
+        .body(Body::empty())
+        .map_err(|e| ClientError::Transport(e.to_string()))?;
+
+    let response = client
+        .request(request)
+        .await
+        .map_err(|e| ClientError::Transport(e.to_string()))?;
+    match response.status() {
+        status if status.is_success() => Ok(()),
+        status => Err(ClientError::Transport(format!("HTTP {}", status))),
+    }
+}
+
+// Parse a received frame and pass it on
+fn forward(incoming_tx: &mpsc::UnboundedSender<Value>, text: &str) {
+    match serde_json::from_str(text) {
+        Ok(message) => forward_value(incoming_tx, message),
+        Err(e) => warn!("Ignoring malformed message from server: {}", e),
+    }
+}
+
+// Pass a message on, unpacking batches
+fn forward_value(incoming_tx: &mpsc::UnboundedSender<Value>, message: Value) {
+    match message {
+        Value::Array(batch) => {
+            for message in batch {
+                let _ = incoming_tx.send(message);
+            }
+        }
+        message => {
+            let _ = incoming_tx.send(message);
+        }
+    }
+}
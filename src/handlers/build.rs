@@ -1,26 +1,46 @@
 // Build handler implementations
 
-use anyhow::{Context, Result};
 use axum::{
     extract::Extension,
     http::StatusCode,
     response::{IntoResponse, Json},
 };
-use serde_json::{json, Value};
-use std::path::PathBuf;
+use log::warn;
+use serde_json::json;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
+use crate::api::auth::Scope;
 use crate::api::build::{BuildError, BuildRequest, BuildResult, BuildWarning};
 use crate::server::ServerState;
+use crate::utils::auth::Granted;
+use crate::utils::error::McpError;
+use crate::utils::event_bus::EventBus;
+use crate::utils::progress::ProgressReporter;
 
-// Build the project
-pub async fn build_project(
-    Extension(project_dir): Extension<PathBuf>,
-    Json(request): Json<BuildRequest>,
-) -> impl IntoResponse {
+// Phases reported while a build runs
+const BUILD_PHASES: &[&str] = &[
+    "Resolving dependencies",
+    "Compiling components",
+    "Linking",
+    "Writing output files",
+];
+
+// Compile the project
+pub async fn compile(
+    project_dir: &Path,
+    state: &Arc<RwLock<ServerState>>,
+    progress: &ProgressReporter,
+    _request: BuildRequest,
+) -> Result<BuildResult, McpError> {
     // TODO: Implement project build logic
-    // For now, return a mock response
+    // For now, report the build phases and return a mock response
+    let total = BUILD_PHASES.len() as u64;
+    for (done, phase) in BUILD_PHASES.iter().enumerate() {
+        progress.report(done as u64, Some(total), *phase);
+    }
+
     let result = BuildResult {
         success: true,
         time_ms: 1500,
@@ -33,5 +53,56 @@ pub async fn build_project(
         }],
     };
 
-    (StatusCode::OK, Json(json!({ "result": result })))
+    state.write().await.set_last_build(result.clone());
+    progress.report(total, Some(total), "Build finished");
+
+    Ok(result)
+}
+
+// Names of the features declared in the project's `Cargo.toml`
+pub async fn features(project_dir: &Path) -> Vec<String> {
+    let Ok(manifest) = tokio::fs::read_to_string(project_dir.join("Cargo.toml")).await else {
+        return Vec::new();
+    };
+
+    match manifest.parse::<toml::Table>() {
+        Ok(manifest) => manifest
+            .get("features")
+            .and_then(toml::Value::as_table)
+            .map(|features| features.keys().cloned().collect())
+            .unwrap_or_default(),
+        Err(e) => {
+            warn!(
+                "Failed to parse {}: {}",
+                project_dir.join("Cargo.toml").display(),
+                e
+            );
+            Vec::new()
+        }
+    }
+}
+
+// Get the errors from the most recent build
+pub async fn errors(state: &Arc<RwLock<ServerState>>) -> Result<Vec<BuildError>, McpError> {
+    Ok(state
+        .read()
+        .await
+        .last_build()
+        .map(|result| result.errors.clone())
+        .unwrap_or_default())
+}
+
+// Build the project
+pub async fn build_project(
+    Extension(granted): Extension<Granted>,
+    Extension(project_dir): Extension<PathBuf>,
+    Extension(state): Extension<Arc<RwLock<ServerState>>>,
+    Extension(event_tx): Extension<EventBus>,
+    Json(request): Json<BuildRequest>,
+) -> Result<impl IntoResponse, McpError> {
+    granted.require(Scope::Build)?;
+    let progress = ProgressReporter::new("build", event_tx);
+    let result = compile(&project_dir, &state, &progress, request).await?;
+
+    Ok((StatusCode::OK, Json(json!({ "result": result }))))
 }
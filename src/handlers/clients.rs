@@ -0,0 +1,98 @@
+// Client identification handler implementations
+
+use log::info;
+use serde_json::Value;
+
+use crate::api::clients::{ClientHelloRequest, ClientHelloResult};
+use crate::api::mcp::Implementation;
+use crate::handlers::rpc::RpcContext;
+use crate::utils::error::McpError;
+
+/// Flatten MCP client capabilities into paths such as `roots.listChanged`
+pub fn capability_paths(capabilities: &Value) -> Vec<String> {
+    let mut paths = Vec::new();
+    collect_capability_paths("", capabilities, &mut paths);
+    paths
+}
+
+// Add the enabled capabilities below `prefix` to `paths`
+fn collect_capability_paths(prefix: &str, capabilities: &Value, paths: &mut Vec<String>) {
+    let Some(capabilities) = capabilities.as_object() else {
+        return;
+    };
+
+    for (name, value) in capabilities {
+        if matches!(value, Value::Null | Value::Bool(false)) {
+            continue;
+        }
+
+        let path = if prefix.is_empty() {
+            name.clone()
+        } else {
+            format!("{}.{}", prefix, name)
+        };
+        collect_capability_paths(&path, value, paths);
+        paths.push(path);
+    }
+}
+
+/// Record who the requesting client is and what it supports
+pub async fn identify(
+    ctx: &RpcContext,
+    name: String,
+    version: Option<String>,
+    protocol_version: Option<String>,
+    capabilities: Vec<String>,
+) {
+    info!(
+        "Client {} identified as {} {} (protocol {}, capabilities: {})",
+        ctx.client_id,
+        name,
+        version.as_deref().unwrap_or("unknown"),
+        protocol_version.as_deref().unwrap_or("unknown"),
+        capabilities.join(", ")
+    );
+
+    let mut state = ctx.state.write().await;
+    // Clients configured by name are narrowed to their configuration; a client
+    // can leave it out by picking another name, so this never grants anything
+    let limit = state.options().client_scopes.get(&name).cloned();
+    if let Some(client) = state.client_mut(&ctx.client_id) {
+        if let Some(limit) = limit {
+            client.scopes = client.scopes.intersection(&limit);
+        }
+        client.name = Some(name);
+        client.version = version;
+        client.protocol_version = protocol_version;
+        client.capabilities = capabilities;
+    }
+}
+
+// Identify a client that doesn't use `initialize`
+pub async fn hello(
+    ctx: &RpcContext,
+    request: ClientHelloRequest,
+) -> Result<ClientHelloResult, McpError> {
+    if request.name.is_empty() {
+        return Err(McpError::InvalidRequest(
+            "Client name must not be empty".to_string(),
+        ));
+    }
+
+    identify(
+        ctx,
+        request.name,
+        request.version,
+        request.protocol_version,
+        request.capabilities,
+    )
+    .await;
+
+    Ok(ClientHelloResult {
+        client_id: ctx.client_id.clone(),
+        server_info: Implementation {
+            name: "orbit-mcp".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        },
+    })
+}
@@ -0,0 +1,130 @@
+// Completion handler implementations (argument autocompletion)
+
+use crate::api::completion::{
+    CompleteRequest, CompleteResult, Completion, Reference, MAX_COMPLETION_VALUES,
+};
+use crate::api::documentation::DocCategory;
+use crate::api::prompts::PromptArgumentKind;
+use crate::handlers::projects::PROJECT_TEMPLATES;
+use crate::handlers::rpc::RpcContext;
+use crate::handlers::{build, components, prompts, resources, roots, testing};
+use crate::utils::error::McpError;
+use crate::utils::fuzzy;
+
+// Where the values of an argument come from
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Source {
+    /// Component IDs from the project's `.orbit` files
+    Components,
+    /// Test names from the project's sources
+    Tests,
+    /// Component template names
+    ComponentTemplates,
+    /// Project template names
+    ProjectTemplates,
+    /// Features from the project's `Cargo.toml`
+    Features,
+    /// Documentation categories
+    DocCategories,
+    /// Projects the client can address
+    Projects,
+}
+
+// Suggest values for a prompt, resource template or tool argument
+pub async fn complete(
+    ctx: &RpcContext,
+    request: CompleteRequest,
+) -> Result<CompleteResult, McpError> {
+    // Nested tool arguments are named by their path, such as `options.filter`
+    let argument = request.argument.name.rsplit('.').next().unwrap_or_default();
+
+    let source = match &request.reference {
+        Reference::Prompt { name } => {
+            let prompts = prompts::list(&ctx.project_dir).await?.prompts;
+            let prompt = prompts
+                .into_iter()
+                .find(|prompt| prompt.name == *name)
+                .ok_or_else(|| McpError::InvalidRequest(format!("Unknown prompt: {}", name)))?;
+            let kind = prompt
+                .arguments
+                .iter()
+                .find(|candidate| candidate.name == request.argument.name)
+                .map(|candidate| candidate.kind);
+
+            if kind == Some(PromptArgumentKind::Component) {
+                Some(Source::Components)
+            } else {
+                argument_source(argument, None)
+            }
+        }
+        Reference::Resource { uri } => {
+            if uri.starts_with(resources::COMPONENT_URI_PREFIX) && argument == "id" {
+                Some(Source::Components)
+            } else {
+                None
+            }
+        }
+        Reference::Tool { name } => argument_source(argument, Some(name)),
+    };
+
+    let candidates = match source {
+        Some(source) => candidates(ctx, source).await,
+        None => Vec::new(),
+    };
+    let mut values = fuzzy::rank(candidates, &request.argument.value);
+
+    let total = values.len();
+    values.truncate(MAX_COMPLETION_VALUES);
+    Ok(CompleteResult {
+        completion: Completion {
+            has_more: total > values.len(),
+            total: Some(total),
+            values,
+        },
+    })
+}
+
+// Pick the source of an argument by its name, and the tool it belongs to
+fn argument_source(argument: &str, tool: Option<&str>) -> Option<Source> {
+    let project_tool = tool.is_some_and(|tool| tool.starts_with("project"));
+
+    match argument {
+        "id" | "component" | "component_id" => Some(Source::Components),
+        "filter" | "test" => Some(Source::Tests),
+        "template" if project_tool => Some(Source::ProjectTemplates),
+        "template" => Some(Source::ComponentTemplates),
+        "features" | "feature" => Some(Source::Features),
+        "category" => Some(Source::DocCategories),
+        "project" => Some(Source::Projects),
+        _ => None,
+    }
+}
+
+// All values an argument can take
+async fn candidates(ctx: &RpcContext, source: Source) -> Vec<String> {
+    match source {
+        Source::Components => components::discover_ids(&ctx.project_dir).await,
+        Source::Tests => testing::discover(&ctx.project_dir).await,
+        Source::ComponentTemplates => components::templates(&ctx.project_dir).await,
+        Source::ProjectTemplates => PROJECT_TEMPLATES
+            .iter()
+            .map(|template| template.to_string())
+            .collect(),
+        Source::Features => build::features(&ctx.project_dir).await,
+        Source::DocCategories => [
+            DocCategory::Api,
+            DocCategory::Guide,
+            DocCategory::Tutorial,
+            DocCategory::Example,
+        ]
+        .iter()
+        .filter_map(|category| serde_json::to_value(category).ok())
+        .filter_map(|category| category.as_str().map(str::to_string))
+        .collect(),
+        Source::Projects => roots::list(ctx, &ctx.project_dir)
+            .await
+            .into_iter()
+            .map(|project| project.name)
+            .collect(),
+    }
+}
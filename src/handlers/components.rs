@@ -1,25 +1,86 @@
 // Component handler implementations
 
-use anyhow::{Context, Result};
 use axum::{
-    body::Body,
     extract::{Extension, Path},
     http::StatusCode,
     response::{IntoResponse, Json},
 };
-use serde_json::{json, Value};
-use std::path::PathBuf;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use serde_json::json;
+use std::path::{Path as FsPath, PathBuf};
 
-use crate::api::components::{Component, CreateComponentRequest, UpdateComponentRequest};
-use crate::server::ServerState;
+use crate::api::auth::Scope;
+use crate::api::components::{
+    Component, ComponentAnalysis, CreateComponentRequest, UpdateComponentRequest,
+};
+use crate::api::sampling::{CreateMessageRequest, SamplingMessage};
+use crate::handlers::rpc::RpcContext;
+use crate::handlers::sampling;
+use crate::utils::auth::Granted;
+use crate::utils::error::McpError;
+use crate::utils::file_utils::{self, DenyList};
+
+// Longest component description to ask the client's model for
+const DESCRIPTION_MAX_TOKENS: u32 = 400;
+
+/// Templates new components can start from
+pub const COMPONENT_TEMPLATES: &[&str] = &["basic", "form", "layout", "list"];
+
+// Directory of the project's own component templates
+const TEMPLATES_DIR: &str = ".orbit/templates";
+
+// Derive a display name from a component ID
+fn component_name(id: &str) -> Result<String, McpError> {
+    let mut chars = id.chars();
+    match chars.next() {
+        Some(first) => Ok(first.to_uppercase().collect::<String>() + chars.as_str()),
+        None => Err(McpError::InvalidRequest(
+            "Component ID must not be empty".to_string(),
+        )),
+    }
+}
+
+// Where the source of a component lives, kept inside the project
+fn component_path(project_dir: &FsPath, denied: &DenyList, id: &str) -> Result<PathBuf, McpError> {
+    file_utils::resolve_project_path(project_dir, denied, format!("src/{}.orbit", id))
+}
+
+// IDs of the components in the project's `.orbit` files
+pub async fn discover_ids(project_dir: &FsPath) -> Vec<String> {
+    file_utils::find_component_files(project_dir, "orbit")
+        .await
+        .unwrap_or_default()
+        .iter()
+        .filter_map(|path| path.file_stem())
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .collect()
+}
+
+// Names of the component templates, built in and from `.orbit/templates`
+pub async fn templates(project_dir: &FsPath) -> Vec<String> {
+    let mut templates: Vec<String> = COMPONENT_TEMPLATES
+        .iter()
+        .map(|template| template.to_string())
+        .collect();
+
+    let Ok(mut entries) = tokio::fs::read_dir(project_dir.join(TEMPLATES_DIR)).await else {
+        return templates;
+    };
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "orbit") {
+            if let Some(stem) = path.file_stem() {
+                templates.push(stem.to_string_lossy().into_owned());
+            }
+        }
+    }
+    templates
+}
 
 // List all components
-pub async fn list_components(Extension(project_dir): Extension<PathBuf>) -> impl IntoResponse {
+pub async fn list(project_dir: &FsPath) -> Result<Vec<Component>, McpError> {
     // TODO: Implement component listing logic
     // For now, return a mock response
-    let components = vec![
+    Ok(vec![
         Component {
             id: "counter".to_string(),
             name: "Counter".to_string(),
@@ -36,92 +97,188 @@ pub async fn list_components(Extension(project_dir): Extension<PathBuf>) -> impl
             children: false,
             events: vec![],
         },
-    ];
-
-    (StatusCode::OK, Json(json!({ "components": components })))
+    ])
 }
 
 // Create a new component
-pub async fn create_component(
-    Extension(project_dir): Extension<PathBuf>,
-    Json(request): Json<CreateComponentRequest>,
-) -> impl IntoResponse {
+pub async fn create(
+    project_dir: &FsPath,
+    denied: &DenyList,
+    request: CreateComponentRequest,
+) -> Result<Component, McpError> {
     // TODO: Implement component creation logic
     // For now, return a mock response
-    let name = request.name.clone();
-    let component = Component {
-        id: name.to_lowercase(),
-        name,
-        path: project_dir.join(format!("src/{}.orbit", request.name.to_lowercase())),
+    if request.name.is_empty() {
+        return Err(McpError::InvalidRequest(
+            "Component name must not be empty".to_string(),
+        ));
+    }
+
+    let id = request.name.to_lowercase();
+    let path = match &request.path {
+        Some(path) => file_utils::resolve_project_path(project_dir, denied, path)?,
+        None => component_path(project_dir, denied, &id)?,
+    };
+    Ok(Component {
+        id,
+        name: request.name,
+        path,
         props: request.props.unwrap_or_default(),
         children: request.has_children.unwrap_or(false),
         events: request.events.unwrap_or_default(),
-    };
-
-    (StatusCode::CREATED, Json(json!({ "component": component })))
+    })
 }
 
 // Get component by ID
-pub async fn get_component(
-    Extension(project_dir): Extension<PathBuf>,
-    Path(id): Path<String>,
-) -> impl IntoResponse {
+pub async fn get(project_dir: &FsPath, denied: &DenyList, id: &str) -> Result<Component, McpError> {
     // TODO: Implement component retrieval logic
     // For now, return a mock response
-    let component = Component {
-        id: id.clone(),
-        name: id
-            .chars()
-            .next()
-            .unwrap()
-            .to_uppercase()
-            .collect::<String>()
-            + &id[1..],
-        path: project_dir.join(format!("src/{}.orbit", id)),
+    Ok(Component {
+        id: id.to_string(),
+        name: component_name(id)?,
+        path: component_path(project_dir, denied, id)?,
         props: vec![],
         children: false,
         events: vec![],
-    };
-
-    (StatusCode::OK, Json(json!({ "component": component })))
+    })
 }
 
 // Update component by ID
-pub async fn update_component(
-    Extension(project_dir): Extension<PathBuf>,
-    Path(id): Path<String>,
-    Json(request): Json<UpdateComponentRequest>,
-) -> impl IntoResponse {
+pub async fn update(
+    project_dir: &FsPath,
+    denied: &DenyList,
+    id: &str,
+    request: UpdateComponentRequest,
+) -> Result<Component, McpError> {
     // TODO: Implement component update logic
     // For now, return a mock response
-    let component = Component {
-        id: id.clone(),
-        name: id
-            .chars()
-            .next()
-            .unwrap()
-            .to_uppercase()
-            .collect::<String>()
-            + &id[1..],
-        path: project_dir.join(format!("src/{}.orbit", id)),
+    Ok(Component {
+        id: id.to_string(),
+        name: component_name(id)?,
+        path: component_path(project_dir, denied, id)?,
         props: request.props.unwrap_or_default(),
         children: false,
         events: request.events.unwrap_or_default(),
+    })
+}
+
+// Delete component by ID
+pub async fn delete(project_dir: &FsPath, denied: &DenyList, id: &str) -> Result<(), McpError> {
+    // TODO: Implement component deletion logic
+    // For now, pretend the deletion succeeded
+    component_name(id)?;
+    component_path(project_dir, denied, id)?;
+    Ok(())
+}
+
+// Analyze component by ID
+pub async fn analyze(
+    project_dir: &FsPath,
+    denied: &DenyList,
+    id: &str,
+) -> Result<ComponentAnalysis, McpError> {
+    // TODO: Implement component analysis logic (via orlint)
+    // For now, return a mock response
+    get(project_dir, denied, id).await?;
+
+    Ok(ComponentAnalysis {
+        complexity: 12,
+        dependencies: vec![],
+        issues: vec![],
+        suggestions: vec![],
+    })
+}
+
+// Describe a component in prose, written by the requesting client's model
+pub async fn describe(ctx: &RpcContext, id: &str) -> Result<String, McpError> {
+    let component = get(&ctx.project_dir, &ctx.deny_list().await, id).await?;
+
+    // The source may not exist yet for components that were only planned
+    let mut prompt = format!(
+        "Write a one-paragraph description of the Orbit component {} for its documentation. \
+         Its props and events are:\n\n{}",
+        component.name,
+        serde_json::to_string_pretty(&component).unwrap_or_default()
+    );
+    if let Ok(source) = tokio::fs::read_to_string(&component.path).await {
+        prompt.push_str(&format!("\n\nIts source is:\n\n{}", source));
+    }
+
+    let request = CreateMessageRequest {
+        messages: vec![SamplingMessage::user(prompt)],
+        system_prompt: Some(
+            "You document UI components. Answer with the description only.".to_string(),
+        ),
+        include_context: Some("none".to_string()),
+        max_tokens: DESCRIPTION_MAX_TOKENS,
+        ..Default::default()
     };
+    sampling::create_text(ctx, request).await
+}
+
+// List all components
+pub async fn list_components(
+    Extension(granted): Extension<Granted>,
+    Extension(project_dir): Extension<PathBuf>,
+) -> Result<impl IntoResponse, McpError> {
+    granted.require(Scope::Read)?;
+    let components = list(&project_dir).await?;
+
+    Ok((StatusCode::OK, Json(json!({ "components": components }))))
+}
+
+// Create a new component
+pub async fn create_component(
+    Extension(granted): Extension<Granted>,
+    Extension(project_dir): Extension<PathBuf>,
+    Extension(denied): Extension<DenyList>,
+    Json(request): Json<CreateComponentRequest>,
+) -> Result<impl IntoResponse, McpError> {
+    granted.require(Scope::Write)?;
+    let component = create(&project_dir, &denied, request).await?;
 
-    (StatusCode::OK, Json(json!({ "component": component })))
+    Ok((StatusCode::CREATED, Json(json!({ "component": component }))))
+}
+
+// Get component by ID
+pub async fn get_component(
+    Extension(granted): Extension<Granted>,
+    Extension(project_dir): Extension<PathBuf>,
+    Extension(denied): Extension<DenyList>,
+    Path(id): Path<String>,
+) -> Result<impl IntoResponse, McpError> {
+    granted.require(Scope::Read)?;
+    let component = get(&project_dir, &denied, &id).await?;
+
+    Ok((StatusCode::OK, Json(json!({ "component": component }))))
+}
+
+// Update component by ID
+pub async fn update_component(
+    Extension(granted): Extension<Granted>,
+    Extension(project_dir): Extension<PathBuf>,
+    Extension(denied): Extension<DenyList>,
+    Path(id): Path<String>,
+    Json(request): Json<UpdateComponentRequest>,
+) -> Result<impl IntoResponse, McpError> {
+    granted.require(Scope::Write)?;
+    let component = update(&project_dir, &denied, &id, request).await?;
+
+    Ok((StatusCode::OK, Json(json!({ "component": component }))))
 }
 
 // Delete component by ID
 pub async fn delete_component(
+    Extension(granted): Extension<Granted>,
     Extension(project_dir): Extension<PathBuf>,
+    Extension(denied): Extension<DenyList>,
     Path(id): Path<String>,
-) -> impl IntoResponse {
-    // TODO: Implement component deletion logic
-    // For now, return a mock response
+) -> Result<impl IntoResponse, McpError> {
+    granted.require(Scope::Write)?;
+    delete(&project_dir, &denied, &id).await?;
 
-    (
+    Ok((
         StatusCode::OK,
         Json(json!({ "success": true, "message": format!("Component {} deleted", id) })),
-    )
+    ))
 }
@@ -1,15 +1,82 @@
 // Documentation handler implementations
 
-use anyhow::{Context, Result};
-use axum::{
-    extract::Extension,
-    http::StatusCode,
-    response::{IntoResponse, Json},
-};
-use serde_json::{json, Value};
 use std::path::PathBuf;
-use std::sync::Arc;
-use tokio::sync::RwLock;
 
-use crate::api::documentation::{DocCategory, DocItem, DocQueryRequest};
-use crate::server::ServerState;
+use crate::api::documentation::{
+    DocCategory, DocExample, DocExampleRequest, DocItem, DocQueryRequest,
+};
+use crate::utils::error::McpError;
+
+// Query the documentation
+pub async fn query(request: DocQueryRequest) -> Result<Vec<DocItem>, McpError> {
+    // TODO: Implement documentation search over the Orbit docs
+    // For now, filter a mock index
+    let index = vec![
+        DocItem {
+            title: "Components".to_string(),
+            path: PathBuf::from("docs/guide/components.md"),
+            category: DocCategory::Guide,
+            description: "Writing .orbit components with props, events and children".to_string(),
+            content: None,
+            related: vec!["Props".to_string()],
+        },
+        DocItem {
+            title: "Props".to_string(),
+            path: PathBuf::from("docs/api/props.md"),
+            category: DocCategory::Api,
+            description: "Declaring and validating component properties".to_string(),
+            content: None,
+            related: vec!["Components".to_string()],
+        },
+        DocItem {
+            title: "Getting Started".to_string(),
+            path: PathBuf::from("docs/tutorial/getting-started.md"),
+            category: DocCategory::Tutorial,
+            description: "Create and build your first Orbit project".to_string(),
+            content: None,
+            related: vec![],
+        },
+    ];
+
+    let query = request.query.to_lowercase();
+    let limit = request.limit.unwrap_or(10);
+
+    Ok(index
+        .into_iter()
+        .filter(|item| {
+            request
+                .category
+                .as_ref()
+                .is_none_or(|category| category == &item.category)
+        })
+        .filter(|item| {
+            item.title.to_lowercase().contains(&query)
+                || item.description.to_lowercase().contains(&query)
+        })
+        .take(limit)
+        .collect())
+}
+
+// Get code examples
+pub async fn examples(request: DocExampleRequest) -> Result<Vec<DocExample>, McpError> {
+    // TODO: Load examples from the Orbit examples directory
+    // For now, return a mock response
+    if request.example_type != "component" {
+        return Ok(vec![]);
+    }
+
+    let example = DocExample {
+        title: "Counter component".to_string(),
+        description: "A component with internal state and a click event".to_string(),
+        code: "<template>\n  <button @click=\"increment\">{{ count }}</button>\n</template>"
+            .to_string(),
+        language: "orbit".to_string(),
+        related: vec![],
+        path: Some(PathBuf::from("examples/counter.orbit")),
+    };
+
+    Ok(vec![example]
+        .into_iter()
+        .take(request.limit.unwrap_or(10))
+        .collect())
+}
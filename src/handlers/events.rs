@@ -0,0 +1,48 @@
+// Event subscription handler implementations
+
+use crate::api::events::{SubscribeEventsRequest, SubscribeEventsResult, UnsubscribeEventsRequest};
+use crate::handlers::rpc::RpcContext;
+use crate::utils::error::McpError;
+use crate::utils::event_filter::EventFilter;
+
+// Subscribe the requesting client to matching events
+pub async fn subscribe(
+    ctx: &RpcContext,
+    request: SubscribeEventsRequest,
+) -> Result<SubscribeEventsResult, McpError> {
+    // File events carry canonical paths, so match globs against the real root
+    let project_root = tokio::fs::canonicalize(&ctx.project_dir)
+        .await
+        .unwrap_or_else(|_| ctx.project_dir.clone());
+    let filter = EventFilter::new(&request.events, &request.paths, &project_root)?;
+
+    let subscription_id = ctx
+        .state
+        .write()
+        .await
+        .subscribe_events(&ctx.client_id, filter);
+
+    Ok(SubscribeEventsResult { subscription_id })
+}
+
+// Cancel one or all of the requesting client's event subscriptions
+pub async fn unsubscribe(
+    ctx: &RpcContext,
+    request: UnsubscribeEventsRequest,
+) -> Result<(), McpError> {
+    let mut state = ctx.state.write().await;
+
+    match request.subscription_id {
+        Some(subscription_id) => {
+            if !state.unsubscribe_events(&ctx.client_id, &subscription_id) {
+                return Err(McpError::InvalidRequest(format!(
+                    "Unknown subscription: {}",
+                    subscription_id
+                )));
+            }
+        }
+        None => state.unsubscribe_all_events(&ctx.client_id),
+    }
+
+    Ok(())
+}
@@ -0,0 +1,71 @@
+// Logging handler implementations
+
+use std::sync::Arc;
+
+use serde_json::json;
+use tokio::sync::{broadcast, RwLock};
+
+use crate::api::auth::Scope;
+use crate::api::jsonrpc::JsonRpcNotification;
+use crate::api::logging::{LoggingLevel, LoggingMessageParams, SetLevelRequest};
+use crate::handlers::rpc::RpcContext;
+use crate::server::ServerState;
+use crate::utils::error::McpError;
+use crate::utils::logging::{self, LogRecord};
+
+// Send the requesting client log messages at or above the given level
+pub async fn set_level(ctx: &RpcContext, request: SetLevelRequest) -> Result<(), McpError> {
+    let mut state = ctx.state.write().await;
+    let client = state
+        .client_mut(&ctx.client_id)
+        .ok_or_else(|| McpError::InvalidRequest("Unknown client".to_string()))?;
+    client.log_level = Some(request.level);
+
+    logging::raise_forward_level(request.level.log_filter());
+    Ok(())
+}
+
+/// Forward log records to the clients that asked for them
+///
+/// Records logged while handling a client's message only go to that client.
+/// Records about no client in particular, which may describe any client's
+/// work, only go to clients with the `admin` scope.
+pub async fn forward_log_records(
+    state: Arc<RwLock<ServerState>>,
+    mut records: broadcast::Receiver<LogRecord>,
+) {
+    loop {
+        let record = match records.recv().await {
+            Ok(record) => record,
+            Err(broadcast::error::RecvError::Lagged(_)) => continue,
+            Err(broadcast::error::RecvError::Closed) => break,
+        };
+
+        let level = LoggingLevel::from(record.level);
+        let mut data = json!({ "message": record.message });
+        if let Some(request_id) = &record.request_id {
+            data["requestId"] = request_id.clone();
+        }
+        let notification = json!(JsonRpcNotification::new(
+            "notifications/message",
+            json!(LoggingMessageParams {
+                level,
+                logger: Some(record.logger),
+                data,
+            }),
+        ));
+
+        // Logging here would feed back into the record stream
+        let state = state.read().await;
+        for client in state.clients() {
+            let wanted = client.log_level.is_some_and(|wanted| level >= wanted);
+            let addressed = match &record.client_id {
+                Some(client_id) => *client_id == client.id,
+                None => state.client_scopes(&client.id).allows(Scope::Admin),
+            };
+            if wanted && addressed {
+                state.send_to_client(&client.id, notification.clone());
+            }
+        }
+    }
+}
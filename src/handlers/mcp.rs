@@ -0,0 +1,210 @@
+// Model Context Protocol lifecycle and tool handlers
+
+use serde_json::json;
+
+use crate::api::jsonrpc::JsonRpcError;
+use crate::api::mcp::{
+    CallToolRequest, CallToolResult, CompletionsCapability, Content, Implementation,
+    InitializeRequest, InitializeResult, ListToolsResult, LoggingCapability, PromptsCapability,
+    ResourcesCapability, ServerCapabilities, Tool, ToolsCapability, LATEST_PROTOCOL_VERSION,
+    SUPPORTED_PROTOCOL_VERSIONS,
+};
+use crate::api::schema;
+use crate::handlers::clients;
+use crate::handlers::rpc::{self, RpcContext};
+
+// A tool exposed over MCP and the JSON-RPC method it wraps
+struct ToolSpec {
+    name: &'static str,
+    method: &'static str,
+}
+
+const TOOLS: &[ToolSpec] = &[
+    ToolSpec {
+        name: "component_list",
+        method: "component.list",
+    },
+    ToolSpec {
+        name: "component_get",
+        method: "component.get",
+    },
+    ToolSpec {
+        name: "component_create",
+        method: "component.create",
+    },
+    ToolSpec {
+        name: "component_update",
+        method: "component.update",
+    },
+    ToolSpec {
+        name: "component_delete",
+        method: "component.delete",
+    },
+    ToolSpec {
+        name: "component_analyze",
+        method: "component.analyze",
+    },
+    ToolSpec {
+        name: "component_describe",
+        method: "component.describe",
+    },
+    ToolSpec {
+        name: "project_list",
+        method: "project.list",
+    },
+    ToolSpec {
+        name: "project_info",
+        method: "project.info",
+    },
+    ToolSpec {
+        name: "project_create",
+        method: "project.create",
+    },
+    ToolSpec {
+        name: "project_update",
+        method: "project.update",
+    },
+    ToolSpec {
+        name: "build_compile",
+        method: "build.compile",
+    },
+    ToolSpec {
+        name: "build_errors",
+        method: "build.errors",
+    },
+    ToolSpec {
+        name: "test_run",
+        method: "test.run",
+    },
+    ToolSpec {
+        name: "test_results",
+        method: "test.results",
+    },
+    ToolSpec {
+        name: "docs_query",
+        method: "docs.query",
+    },
+    ToolSpec {
+        name: "docs_examples",
+        method: "docs.examples",
+    },
+];
+
+// Negotiate the protocol version and advertise server capabilities
+pub async fn initialize(
+    ctx: &RpcContext,
+    request: InitializeRequest,
+) -> Result<InitializeResult, JsonRpcError> {
+    // Echo the requested version if we support it, otherwise offer our latest
+    let protocol_version =
+        if SUPPORTED_PROTOCOL_VERSIONS.contains(&request.protocol_version.as_str()) {
+            request.protocol_version.clone()
+        } else {
+            LATEST_PROTOCOL_VERSION.to_string()
+        };
+
+    clients::identify(
+        ctx,
+        request.client_info.name,
+        Some(request.client_info.version),
+        Some(protocol_version.clone()),
+        clients::capability_paths(&request.capabilities),
+    )
+    .await;
+
+    Ok(InitializeResult {
+        protocol_version,
+        capabilities: ServerCapabilities {
+            tools: Some(ToolsCapability {
+                list_changed: false,
+            }),
+            resources: Some(ResourcesCapability {
+                subscribe: true,
+                list_changed: false,
+            }),
+            prompts: Some(PromptsCapability {
+                list_changed: false,
+            }),
+            logging: Some(LoggingCapability {}),
+            completions: Some(CompletionsCapability {}),
+        },
+        server_info: Implementation {
+            name: "orbit-mcp".to_string(),
+            version: env!("CARGO_PKG_VERSION").to_string(),
+        },
+        instructions: Some(
+            "Tools for managing components, building and testing an Orbit project.".to_string(),
+        ),
+    })
+}
+
+// List the available tools
+pub async fn list_tools() -> Result<ListToolsResult, JsonRpcError> {
+    Ok(ListToolsResult {
+        tools: TOOLS
+            .iter()
+            .map(|tool| Tool {
+                name: tool.name.to_string(),
+                description: schema::rpc_method(tool.method)
+                    .map(|method| method.description.to_string())
+                    .unwrap_or_default(),
+                input_schema: schema::params_schema(tool.method),
+            })
+            .collect(),
+    })
+}
+
+// Call a tool by dispatching to the JSON-RPC method it wraps
+pub async fn call_tool(
+    ctx: &RpcContext,
+    request: CallToolRequest,
+) -> Result<CallToolResult, JsonRpcError> {
+    let method = TOOLS
+        .iter()
+        .find(|tool| tool.name == request.name)
+        .map(|tool| tool.method)
+        .ok_or_else(|| JsonRpcError::invalid_params(format!("Unknown tool: {}", request.name)))?;
+
+    let arguments = request.arguments.unwrap_or_else(|| json!({}));
+
+    let result = Box::pin(rpc::dispatch(ctx, method, Some(arguments))).await;
+
+    // Failures inside the tool are reported in the result so the model can see them
+    Ok(match result {
+        Ok(result) => CallToolResult {
+            content: vec![Content::Text {
+                text: serde_json::to_string_pretty(&result).unwrap_or_default(),
+            }],
+            structured_content: Some(result),
+            is_error: false,
+        },
+        Err(e) => CallToolResult {
+            content: vec![Content::Text { text: e.message }],
+            structured_content: None,
+            is_error: true,
+        },
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn every_tool_runs_a_documented_method() {
+        for tool in TOOLS {
+            assert!(
+                rpc::METHODS.contains(&tool.method),
+                "{} runs {}, which isn't dispatched",
+                tool.name,
+                tool.method
+            );
+            assert!(
+                schema::rpc_method(tool.method).is_some(),
+                "{} runs {}, which has no schema",
+                tool.name,
+                tool.method
+            );
+        }
+    }
+}
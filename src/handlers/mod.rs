@@ -1,54 +1,119 @@
 // Handler module definition
 
 pub mod build;
+pub mod clients;
+pub mod completion;
 pub mod components;
 pub mod documentation;
+pub mod events;
+pub mod logging;
+pub mod mcp;
 pub mod projects;
+pub mod prompts;
+pub mod resources;
+pub mod roots;
+pub mod rpc;
+pub mod sampling;
 pub mod testing;
 
 use axum::{
     http::StatusCode,
     response::{IntoResponse, Json},
+    Extension,
 };
-use serde_json::{json, Value};
-use std::path::PathBuf;
+use serde_json::{json, Map, Value};
 use std::sync::Arc;
 use tokio::sync::RwLock;
 
-use crate::server::ServerState;
+use crate::api::auth::Scope;
+use crate::api::schema;
+use crate::server::{ClientInfo, ServerState};
+use crate::utils::auth::Granted;
+use crate::utils::error::McpError;
 
 // Handle status requests
 pub async fn status_handler(
-    axum::extract::Extension(state): axum::extract::Extension<Arc<RwLock<ServerState>>>,
-) -> impl IntoResponse {
+    Extension(granted): Extension<Granted>,
+    Extension(state): Extension<Arc<RwLock<ServerState>>>,
+) -> Result<impl IntoResponse, McpError> {
+    granted.require(Scope::Read)?;
     let state = state.read().await;
+    // Client IDs double as Streamable HTTP session IDs, so only admins see them
+    let show_ids = granted.0.allows(Scope::Admin);
     let status = json!({
         "status": "running",
         "active_connections": state.active_connections(),
-        "clients": state.clients(),
+        "clients": client_list(state.clients(), show_ids),
+        "recent_clients": client_list(state.recent_clients(), show_ids),
         "version": env!("CARGO_PKG_VERSION"),
+        "tls_fingerprint": state.tls_fingerprint(),
         "uptime_seconds": 0, // TODO: Track uptime
     });
 
-    (StatusCode::OK, Json(status))
+    Ok((StatusCode::OK, Json(status)))
+}
+
+// List clients for the status, without their IDs unless `show_ids`
+fn client_list<'a>(
+    clients: impl IntoIterator<Item = &'a ClientInfo>,
+    show_ids: bool,
+) -> Vec<Value> {
+    clients
+        .into_iter()
+        .map(|client| {
+            let mut client = json!(client);
+            if let (false, Some(fields)) = (show_ids, client.as_object_mut()) {
+                fields.remove("id");
+            }
+            client
+        })
+        .collect()
 }
 
 // Handle documentation requests
-pub async fn docs_handler() -> impl IntoResponse {
+pub async fn docs_handler(
+    Extension(granted): Extension<Granted>,
+) -> Result<impl IntoResponse, McpError> {
+    granted.require(Scope::Read)?;
+    let mut endpoints: Map<String, Value> = schema::REST_OPERATIONS
+        .iter()
+        .map(|operation| {
+            (
+                format!("{} {}", operation.method.to_uppercase(), operation.path),
+                json!(operation.summary),
+            )
+        })
+        .collect();
+    endpoints.insert(
+        "GET /api/ws".to_string(),
+        json!("WebSocket endpoint for JSON-RPC communication"),
+    );
+    endpoints.insert(
+        "POST /api/mcp".to_string(),
+        json!("Streamable HTTP endpoint for JSON-RPC communication"),
+    );
+
+    let methods: Map<String, Value> = schema::RPC_METHODS
+        .iter()
+        .map(|method| (method.name.to_string(), json!(method.description)))
+        .collect();
+
     let docs = json!({
         "title": "Orbit MCP Server API",
         "description": "API documentation for the Orbit MCP Server",
         "version": env!("CARGO_PKG_VERSION"),
-        "endpoints": {
-            "api/status": "Get server status",
-            "api/docs": "Get API documentation",
-            "api/ws": "WebSocket endpoint for JSON-RPC communication",
-            "api/components": "Component management",
-            "api/project": "Project information",
-            "api/project/build": "Build project",
-            "api/project/test": "Run tests"
-        }
+        "openapi": "/api/openapi.json",
+        "endpoints": endpoints,
+        "methods": methods,
     });
 
-    (StatusCode::OK, Json(docs))
+    Ok((StatusCode::OK, Json(docs)))
+}
+
+// Handle OpenAPI document requests
+pub async fn openapi_handler(
+    Extension(granted): Extension<Granted>,
+) -> Result<impl IntoResponse, McpError> {
+    granted.require(Scope::Read)?;
+    Ok((StatusCode::OK, Json(schema::openapi())))
 }
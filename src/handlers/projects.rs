@@ -1,26 +1,31 @@
 // Project handler implementations
 
-use anyhow::{Context, Result};
 use axum::{
     extract::Extension,
     http::StatusCode,
     response::{IntoResponse, Json},
 };
-use serde_json::{json, Value};
-use std::path::PathBuf;
-use std::sync::Arc;
-use tokio::sync::RwLock;
+use serde_json::json;
+use std::path::{Path, PathBuf};
 
-use crate::api::projects::{Dependency, Project, ProjectStructure};
-use crate::server::ServerState;
+use crate::api::auth::Scope;
+use crate::api::projects::{
+    CreateProjectRequest, Dependency, Project, ProjectStructure, UpdateProjectRequest,
+};
+use crate::utils::auth::Granted;
+use crate::utils::error::McpError;
+use crate::utils::file_utils::{self, DenyList};
+
+/// Templates new projects can start from
+pub const PROJECT_TEMPLATES: &[&str] = &["default", "library", "spa"];
 
 // Get project information
-pub async fn get_project_info(Extension(project_dir): Extension<PathBuf>) -> impl IntoResponse {
+pub async fn info(project_dir: &Path) -> Result<Project, McpError> {
     // TODO: Implement project info retrieval logic
     // For now, return a mock response
-    let project = Project {
+    Ok(Project {
         name: "orbit-example".to_string(),
-        path: project_dir.clone(),
+        path: project_dir.to_path_buf(),
         version: "0.1.0".to_string(),
         description: Some("Orbit example project".to_string()),
         dependencies: vec![
@@ -43,7 +48,73 @@ pub async fn get_project_info(Extension(project_dir): Extension<PathBuf>) -> imp
             assets: vec![],
             tests: vec![],
         },
-    };
+    })
+}
+
+// Create a new project
+pub async fn create(
+    project_dir: &Path,
+    denied: &DenyList,
+    request: CreateProjectRequest,
+) -> Result<Project, McpError> {
+    // TODO: Implement project scaffolding logic
+    // For now, return a mock response
+    if request.name.is_empty() {
+        return Err(McpError::InvalidRequest(
+            "Project name must not be empty".to_string(),
+        ));
+    }
+
+    let path = request.path.as_deref().unwrap_or(Path::new(&request.name));
+    Ok(Project {
+        path: file_utils::resolve_project_path(project_dir, denied, path)?,
+        name: request.name,
+        version: "0.1.0".to_string(),
+        description: request.description,
+        dependencies: request.dependencies.unwrap_or_default(),
+        structure: ProjectStructure {
+            components: vec![],
+            assets: vec![],
+            tests: vec![],
+        },
+    })
+}
+
+// Update project configuration
+pub async fn update(
+    project_dir: &Path,
+    request: UpdateProjectRequest,
+) -> Result<Project, McpError> {
+    // TODO: Implement project update logic
+    // For now, apply the changes to the mock project
+    let mut project = info(project_dir).await?;
+
+    if let Some(name) = request.name {
+        project.name = name;
+    }
+    if let Some(description) = request.description {
+        project.description = Some(description);
+    }
+    if let Some(version) = request.version {
+        project.version = version;
+    }
+    if let Some(remove) = request.remove_dependencies {
+        project.dependencies.retain(|d| !remove.contains(&d.name));
+    }
+    if let Some(mut add) = request.add_dependencies {
+        project.dependencies.append(&mut add);
+    }
+
+    Ok(project)
+}
+
+// Get project information
+pub async fn get_project_info(
+    Extension(granted): Extension<Granted>,
+    Extension(project_dir): Extension<PathBuf>,
+) -> Result<impl IntoResponse, McpError> {
+    granted.require(Scope::Read)?;
+    let project = info(&project_dir).await?;
 
-    (StatusCode::OK, Json(json!({ "project": project })))
+    Ok((StatusCode::OK, Json(json!({ "project": project }))))
 }
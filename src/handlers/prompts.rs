@@ -0,0 +1,320 @@
+// Prompt handler implementations
+
+use anyhow::Context;
+use log::warn;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use tokio::sync::RwLock;
+
+use crate::api::mcp::Content;
+use crate::api::prompts::{
+    GetPromptRequest, GetPromptResult, ListPromptsResult, Prompt, PromptArgument,
+    PromptArgumentKind, PromptMessage, PromptTemplate, Role,
+};
+use crate::handlers::resources::{self, COMPONENT_URI_PREFIX};
+use crate::server::ServerState;
+use crate::utils::error::McpError;
+use crate::utils::file_utils::{read_component_file, DenyList};
+
+/// Directory, relative to the project root, holding project-local prompts
+pub const PROMPTS_DIR: &str = ".orbit/prompts";
+
+// Describe a prompt argument
+fn argument(
+    name: &str,
+    description: &str,
+    required: bool,
+    kind: PromptArgumentKind,
+) -> PromptArgument {
+    PromptArgument {
+        name: name.to_string(),
+        description: Some(description.to_string()),
+        required,
+        kind,
+    }
+}
+
+// Prompts that ship with the server
+fn builtin_templates() -> Vec<PromptTemplate> {
+    vec![
+        PromptTemplate {
+            prompt: Prompt {
+                name: "create_component".to_string(),
+                description: Some("Create a new component".to_string()),
+                arguments: vec![
+                    argument(
+                        "name",
+                        "Component name in PascalCase",
+                        true,
+                        PromptArgumentKind::String,
+                    ),
+                    argument(
+                        "props",
+                        "Props as comma-separated `name: Type` pairs",
+                        false,
+                        PromptArgumentKind::String,
+                    ),
+                    argument(
+                        "description",
+                        "What the component should do",
+                        false,
+                        PromptArgumentKind::String,
+                    ),
+                ],
+            },
+            template: "Create a new Orbit component named `{{name}}`.\n\n\
+                       Props (if any): {{props}}\n\n\
+                       Purpose: {{description}}\n\n\
+                       Use the `component_create` tool to create it, then check the result \
+                       with `component_analyze`."
+                .to_string(),
+        },
+        PromptTemplate {
+            prompt: Prompt {
+                name: "fix_build_errors".to_string(),
+                description: Some("Fix the errors reported by the last build".to_string()),
+                arguments: vec![],
+            },
+            template: "These are the errors from the last build of this Orbit project:\n\n\
+                       {{build_errors}}\n\n\
+                       Find the cause of each error and fix it, then run `build_compile` \
+                       again to confirm the build passes."
+                .to_string(),
+        },
+        PromptTemplate {
+            prompt: Prompt {
+                name: "write_tests".to_string(),
+                description: Some("Write tests for a component".to_string()),
+                arguments: vec![argument(
+                    "component",
+                    "ID of the component to test",
+                    true,
+                    PromptArgumentKind::Component,
+                )],
+            },
+            template: "Write tests for the Orbit component `{{component}}`.\n\n\
+                       Its source:\n\n```orbit\n{{component.source}}\n```\n\n\
+                       Failures from the last test run:\n\n{{test_failures}}\n\n\
+                       Cover its props, events and rendering, then run the tests with \
+                       `test_run`."
+                .to_string(),
+        },
+    ]
+}
+
+// Load a prompt definition from a JSON file
+async fn load_template(path: &Path) -> anyhow::Result<PromptTemplate> {
+    let text = tokio::fs::read_to_string(path)
+        .await
+        .context("Failed to read prompt file")?;
+    serde_json::from_str(&text).context("Invalid prompt definition")
+}
+
+// Collect the built-in prompts, overridden or extended by the project's own
+async fn load_templates(project_dir: &Path) -> Vec<PromptTemplate> {
+    let mut templates = builtin_templates();
+
+    let Ok(mut entries) = tokio::fs::read_dir(project_dir.join(PROMPTS_DIR)).await else {
+        return templates;
+    };
+
+    let mut paths: Vec<PathBuf> = Vec::new();
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if path.extension().is_some_and(|ext| ext == "json") {
+            paths.push(path);
+        }
+    }
+    paths.sort();
+
+    for path in paths {
+        match load_template(&path).await {
+            Ok(template) => {
+                templates.retain(|existing| existing.prompt.name != template.prompt.name);
+                templates.push(template);
+            }
+            Err(e) => warn!("Skipping prompt {}: {:#}", path.display(), e),
+        }
+    }
+
+    templates
+}
+
+// Substitute `{{key}}` placeholders, leaving unknown ones untouched
+fn render(template: &str, values: &HashMap<String, String>) -> String {
+    let mut output = String::with_capacity(template.len());
+    let mut rest = template;
+
+    while let Some(start) = rest.find("{{") {
+        output.push_str(&rest[..start]);
+
+        let Some(len) = rest[start..].find("}}") else {
+            output.push_str(&rest[start..]);
+            return output;
+        };
+        let placeholder = &rest[start..start + len + 2];
+        let key = placeholder[2..placeholder.len() - 2].trim();
+
+        match values.get(key) {
+            Some(value) => output.push_str(value),
+            None => output.push_str(placeholder),
+        }
+        rest = &rest[start + len + 2..];
+    }
+
+    output.push_str(rest);
+    output
+}
+
+// Summarize the errors of the last build
+fn build_errors_context(state: &ServerState) -> String {
+    let Some(build) = state.last_build() else {
+        return "No build has been run yet.".to_string();
+    };
+
+    if build.errors.is_empty() {
+        return "The last build reported no errors.".to_string();
+    }
+
+    build
+        .errors
+        .iter()
+        .map(|error| match &error.location {
+            Some(location) => format!(
+                "- [{}] {}:{}:{}: {}",
+                error.code,
+                location.file.display(),
+                location.line,
+                location.column,
+                error.message
+            ),
+            None => format!("- [{}] {}", error.code, error.message),
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Summarize the failures of the last test run
+fn test_failures_context(state: &ServerState) -> String {
+    let Some(test) = state.last_test() else {
+        return "No tests have been run yet.".to_string();
+    };
+
+    if test.failures.is_empty() {
+        return "All tests passed.".to_string();
+    }
+
+    test.failures
+        .iter()
+        .map(|failure| {
+            format!(
+                "- {} ({}:{}): {}",
+                failure.test_name,
+                failure.location.file.display(),
+                failure.location.line,
+                failure.message
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+// Read the source of a component by ID
+async fn component_source(
+    project_dir: &Path,
+    denied: &DenyList,
+    id: &str,
+) -> Result<String, McpError> {
+    let uri = format!("{}{}", COMPONENT_URI_PREFIX, id);
+    let path = resources::resolve_uri(project_dir, denied, &uri)
+        .await
+        .map_err(|_| McpError::ComponentNotFound(id.to_string()))?;
+
+    read_component_file(&path)
+        .await
+        .map_err(|e| McpError::ServerError(format!("{:#}", e)))
+}
+
+// Check an argument value against its declared type
+fn validate_argument(argument: &PromptArgument, value: &str) -> Result<(), McpError> {
+    let valid = match argument.kind {
+        PromptArgumentKind::String | PromptArgumentKind::Component => true,
+        PromptArgumentKind::Integer => value.trim().parse::<i64>().is_ok(),
+        PromptArgumentKind::Boolean => matches!(value.trim(), "true" | "false"),
+    };
+
+    if valid {
+        Ok(())
+    } else {
+        Err(McpError::InvalidRequest(format!(
+            "Argument {} must be {}, got {:?}",
+            argument.name,
+            format!("{:?}", argument.kind).to_lowercase(),
+            value
+        )))
+    }
+}
+
+// List the available prompts
+pub async fn list(project_dir: &Path) -> Result<ListPromptsResult, McpError> {
+    Ok(ListPromptsResult {
+        prompts: load_templates(project_dir)
+            .await
+            .into_iter()
+            .map(|template| template.prompt)
+            .collect(),
+    })
+}
+
+// Render a prompt with its arguments and the current project context
+pub async fn get(
+    project_dir: &Path,
+    state: &Arc<RwLock<ServerState>>,
+    request: GetPromptRequest,
+) -> Result<GetPromptResult, McpError> {
+    let template = load_templates(project_dir)
+        .await
+        .into_iter()
+        .find(|template| template.prompt.name == request.name)
+        .ok_or_else(|| McpError::InvalidRequest(format!("Unknown prompt: {}", request.name)))?;
+
+    let mut values = HashMap::new();
+    for argument in &template.prompt.arguments {
+        let Some(value) = request.arguments.get(&argument.name) else {
+            if argument.required {
+                return Err(McpError::InvalidRequest(format!(
+                    "Missing required argument: {}",
+                    argument.name
+                )));
+            }
+            values.insert(argument.name.clone(), String::new());
+            continue;
+        };
+
+        validate_argument(argument, value)?;
+
+        if argument.kind == PromptArgumentKind::Component {
+            let denied = state.read().await.deny_list().clone();
+            let source = component_source(project_dir, &denied, value).await?;
+            values.insert(format!("{}.source", argument.name), source);
+        }
+        values.insert(argument.name.clone(), value.clone());
+    }
+
+    {
+        let state = state.read().await;
+        values.insert("build_errors".to_string(), build_errors_context(&state));
+        values.insert("test_failures".to_string(), test_failures_context(&state));
+    }
+
+    Ok(GetPromptResult {
+        description: template.prompt.description.clone(),
+        messages: vec![PromptMessage {
+            role: Role::User,
+            content: Content::Text {
+                text: render(&template.template, &values),
+            },
+        }],
+    })
+}
@@ -0,0 +1,251 @@
+// Resource handler implementations
+
+use log::debug;
+use serde_json::json;
+use std::collections::HashSet;
+use std::ffi::OsStr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{mpsc, RwLock};
+
+use crate::api::jsonrpc::JsonRpcNotification;
+use crate::api::resources::{
+    ListResourceTemplatesResult, ListResourcesResult, ReadResourceResult, Resource,
+    ResourceContents, ResourceTemplate,
+};
+use crate::handlers::rpc::RpcContext;
+use crate::server::{ServerEvent, ServerState};
+use crate::utils::error::McpError;
+use crate::utils::event_bus::EventBus;
+use crate::utils::file_utils::{self, find_component_files, read_component_file, DenyList};
+
+/// URI prefix for components, followed by the component ID
+pub const COMPONENT_URI_PREFIX: &str = "orbit://component/";
+
+/// URI prefix for project files, followed by the path relative to the project root
+pub const FILE_URI_PREFIX: &str = "orbit://file/";
+
+// Project files exposed as resources when present
+const PROJECT_FILES: &[&str] = &["Cargo.toml", "README.md"];
+
+// How long to wait for more changes to the same file before notifying
+const CHANGE_DEBOUNCE: Duration = Duration::from_millis(100);
+
+// Guess the MIME type of a project file
+fn mime_type(path: &Path) -> &'static str {
+    match path.extension().and_then(OsStr::to_str) {
+        Some("orbit") => "text/x-orbit",
+        Some("toml") => "application/toml",
+        Some("md") => "text/markdown",
+        Some("rs") => "text/x-rust",
+        Some("json") => "application/json",
+        _ => "text/plain",
+    }
+}
+
+// Path of a project file relative to the project root, with forward slashes
+fn relative_path(project_dir: &Path, path: &Path) -> String {
+    path.strip_prefix(project_dir)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/")
+}
+
+// Describe a project file as a resource
+fn file_resource(project_dir: &Path, path: &Path) -> Resource {
+    let relative = relative_path(project_dir, path);
+
+    Resource {
+        uri: format!("{}{}", FILE_URI_PREFIX, relative),
+        name: relative,
+        description: None,
+        mime_type: Some(mime_type(path).to_string()),
+    }
+}
+
+// Find the component files in the project
+async fn component_files(project_dir: &Path) -> Result<Vec<PathBuf>, McpError> {
+    find_component_files(project_dir, "orbit")
+        .await
+        .map_err(|e| McpError::ServerError(format!("Failed to scan components: {:#}", e)))
+}
+
+/// Resolve a resource URI to the file that backs it
+pub async fn resolve_uri(
+    project_dir: &Path,
+    denied: &DenyList,
+    uri: &str,
+) -> Result<PathBuf, McpError> {
+    if let Some(id) = uri.strip_prefix(COMPONENT_URI_PREFIX) {
+        let path = component_files(project_dir)
+            .await?
+            .into_iter()
+            .find(|path| path.file_stem() == Some(OsStr::new(id)))
+            .ok_or_else(|| McpError::ResourceNotFound(uri.to_string()))?;
+        // The file found may still be a symlink to somewhere else
+        return file_utils::resolve_project_path(project_dir, denied, path);
+    }
+
+    if let Some(relative) = uri.strip_prefix(FILE_URI_PREFIX) {
+        let path = file_utils::resolve_project_path(project_dir, denied, relative)?;
+        if !path.is_file() {
+            return Err(McpError::ResourceNotFound(uri.to_string()));
+        }
+        return Ok(path);
+    }
+
+    Err(McpError::ResourceNotFound(uri.to_string()))
+}
+
+// List the resources in the project, leaving out those clients can't read
+pub async fn list(project_dir: &Path, denied: &DenyList) -> Result<ListResourcesResult, McpError> {
+    let readable =
+        |path: &Path| file_utils::resolve_project_path(project_dir, denied, path).is_ok();
+    let mut resources = Vec::new();
+
+    for path in component_files(project_dir).await? {
+        if !readable(&path) {
+            continue;
+        }
+        let Some(id) = path
+            .file_stem()
+            .map(|stem| stem.to_string_lossy().to_string())
+        else {
+            continue;
+        };
+
+        resources.push(Resource {
+            uri: format!("{}{}", COMPONENT_URI_PREFIX, id),
+            description: Some(format!(
+                "Orbit component at {}",
+                relative_path(project_dir, &path)
+            )),
+            name: id,
+            mime_type: Some(mime_type(&path).to_string()),
+        });
+    }
+
+    for name in PROJECT_FILES {
+        let path = project_dir.join(name);
+        if path.is_file() && readable(&path) {
+            resources.push(file_resource(project_dir, &path));
+        }
+    }
+
+    let docs_dir = project_dir.join("docs");
+    if docs_dir.is_dir() {
+        let docs = find_component_files(&docs_dir, "md")
+            .await
+            .map_err(|e| McpError::ServerError(format!("Failed to scan docs: {:#}", e)))?;
+        for path in docs.into_iter().filter(|path| readable(path)) {
+            resources.push(file_resource(project_dir, &path));
+        }
+    }
+
+    Ok(ListResourcesResult { resources })
+}
+
+// List the resource templates
+pub async fn templates() -> Result<ListResourceTemplatesResult, McpError> {
+    Ok(ListResourceTemplatesResult {
+        resource_templates: vec![
+            ResourceTemplate {
+                uri_template: format!("{}{{id}}", COMPONENT_URI_PREFIX),
+                name: "Component source".to_string(),
+                description: Some("Source of an .orbit component, by component ID".to_string()),
+                mime_type: Some(mime_type(Path::new("x.orbit")).to_string()),
+            },
+            ResourceTemplate {
+                uri_template: format!("{}{{path}}", FILE_URI_PREFIX),
+                name: "Project file".to_string(),
+                description: Some("Any file, by path relative to the project root".to_string()),
+                mime_type: None,
+            },
+        ],
+    })
+}
+
+// Read a resource
+pub async fn read(
+    project_dir: &Path,
+    denied: &DenyList,
+    uri: &str,
+) -> Result<ReadResourceResult, McpError> {
+    let path = resolve_uri(project_dir, denied, uri).await?;
+    let text = read_component_file(&path)
+        .await
+        .map_err(|e| McpError::ServerError(format!("{:#}", e)))?;
+
+    Ok(ReadResourceResult {
+        contents: vec![ResourceContents {
+            uri: uri.to_string(),
+            mime_type: Some(mime_type(&path).to_string()),
+            text,
+        }],
+    })
+}
+
+// Subscribe the requesting client to updates of a resource
+pub async fn subscribe(ctx: &RpcContext, uri: &str) -> Result<(), McpError> {
+    let path = resolve_uri(&ctx.project_dir, &ctx.deny_list().await, uri).await?;
+
+    // Watch the real location so events match no matter how the project path was given
+    let path = tokio::fs::canonicalize(&path).await?;
+
+    ctx.state
+        .write()
+        .await
+        .subscribe_resource(&ctx.client_id, &ctx.project_dir, uri, &path)
+        .map_err(|e| McpError::ServerError(format!("{:#}", e)))
+}
+
+// Unsubscribe the requesting client from updates of a resource
+pub async fn unsubscribe(ctx: &RpcContext, uri: &str) -> Result<(), McpError> {
+    ctx.state
+        .write()
+        .await
+        .unsubscribe_resource(&ctx.client_id, &ctx.project_dir, uri);
+
+    Ok(())
+}
+
+/// Notify subscribed clients about changed files until the watcher goes away
+pub async fn forward_file_changes(
+    state: Arc<RwLock<ServerState>>,
+    event_tx: EventBus,
+    mut changes: mpsc::UnboundedReceiver<PathBuf>,
+) {
+    while let Some(path) = changes.recv().await {
+        // A single save usually produces several events, so collect them first
+        let mut changed = HashSet::from([path]);
+        tokio::time::sleep(CHANGE_DEBOUNCE).await;
+        while let Ok(path) = changes.try_recv() {
+            changed.insert(path);
+        }
+
+        let state = state.read().await;
+        for path in changed {
+            for (project_dir, uri, clients) in state.resource_subscribers(&path) {
+                debug!(
+                    "Resource {} changed, notifying {} client(s)",
+                    uri,
+                    clients.len()
+                );
+
+                let notification = json!(JsonRpcNotification::new(
+                    "notifications/resources/updated",
+                    json!({ "uri": uri, "project": project_dir }),
+                ));
+                for client_id in clients {
+                    state.send_to_client(client_id, notification.clone());
+                }
+
+                event_tx.send(ServerEvent::new(
+                    "file.changed",
+                    json!({ "path": path, "uri": uri, "project": project_dir }),
+                ));
+            }
+        }
+    }
+}
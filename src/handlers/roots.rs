@@ -0,0 +1,169 @@
+// Root handler implementations (projects the client has open)
+
+use std::path::{Path, PathBuf};
+
+use log::{info, warn};
+use serde_json::{json, Value};
+
+use crate::api::projects::ProjectRoot;
+use crate::api::roots::ListRootsResult;
+use crate::handlers::rpc::RpcContext;
+use crate::utils::error::McpError;
+use crate::utils::file_utils;
+
+// Ask the requesting client for its roots and make them its projects
+pub async fn refresh(ctx: &RpcContext) -> Result<(), McpError> {
+    let result: ListRootsResult = ctx.request_client("roots/list", json!({})).await?;
+
+    let allowed = allowed_dirs(ctx).await;
+    let denied = ctx.deny_list().await;
+
+    let mut projects = Vec::new();
+    for root in result.roots {
+        let Some(path) = file_utils::file_uri_to_path(&root.uri) else {
+            warn!(
+                "Ignoring root {} of client {}: not a local file URI",
+                root.uri, ctx.client_id
+            );
+            continue;
+        };
+        // Each root becomes a sandbox of its own, so it must lie somewhere
+        // the operator let clients work
+        let Some(path) = inside_allowed(&path, &allowed) else {
+            warn!(
+                "Ignoring root {} of client {}: outside the project and the allowed roots",
+                root.uri, ctx.client_id
+            );
+            continue;
+        };
+        // A root inside .git or the like would expose it as a project
+        if denied.is_denied_path(&path) {
+            warn!(
+                "Ignoring root {} of client {}: on the deny-list",
+                root.uri, ctx.client_id
+            );
+            continue;
+        }
+        let name = root.name.unwrap_or_else(|| directory_name(&path));
+        projects.push(ProjectRoot {
+            name,
+            path,
+            uri: Some(root.uri),
+        });
+    }
+
+    info!(
+        "Client {} has {} root(s): {}",
+        ctx.client_id,
+        projects.len(),
+        projects
+            .iter()
+            .map(|project| project.name.as_str())
+            .collect::<Vec<_>>()
+            .join(", ")
+    );
+
+    let mut state = ctx.state.write().await;
+    if let Some(client) = state.client_mut(&ctx.client_id) {
+        client.roots = projects;
+    }
+    Ok(())
+}
+
+// Refresh the requesting client's roots in the background, if it has any
+//
+// Runs on its own so the notification that triggered it isn't held up by the
+// round trip to the client.
+pub async fn refresh_in_background(ctx: &RpcContext) {
+    let supported = ctx
+        .state
+        .read()
+        .await
+        .client(&ctx.client_id)
+        .is_some_and(|client| client.accepts_request("roots/list"));
+    if !supported {
+        return;
+    }
+
+    let ctx = ctx.clone();
+    tokio::spawn(async move {
+        if let Err(e) = refresh(&ctx).await {
+            warn!("Failed to list roots of client {}: {}", ctx.client_id, e);
+        }
+    });
+}
+
+// List the projects the requesting client can address
+pub async fn list(ctx: &RpcContext, default_dir: &Path) -> Vec<ProjectRoot> {
+    let roots = ctx
+        .state
+        .read()
+        .await
+        .client(&ctx.client_id)
+        .map(|client| client.roots.clone())
+        .unwrap_or_default();
+
+    if roots.is_empty() {
+        vec![ProjectRoot {
+            name: directory_name(default_dir),
+            path: default_dir.to_path_buf(),
+            uri: None,
+        }]
+    } else {
+        roots
+    }
+}
+
+/// Find the directory of the project a request addresses with its `project` param
+///
+/// Projects are addressed by name, root URI or path. Without a `project`
+/// param the client's first root is used, or `default_dir` for clients
+/// without roots.
+pub async fn resolve(
+    ctx: &RpcContext,
+    default_dir: &Path,
+    params: Option<&Value>,
+) -> Result<PathBuf, McpError> {
+    let requested = params
+        .and_then(|params| params.get("project"))
+        .and_then(Value::as_str);
+    let projects = list(ctx, default_dir).await;
+
+    match requested {
+        None => Ok(projects[0].path.clone()),
+        Some(requested) => projects
+            .into_iter()
+            .find(|project| {
+                project.name == requested
+                    || project.uri.as_deref() == Some(requested)
+                    || project.path == Path::new(requested)
+            })
+            .map(|project| project.path)
+            .ok_or_else(|| McpError::ProjectNotFound(requested.to_string())),
+    }
+}
+
+// The directories client roots may lie in, canonicalized
+async fn allowed_dirs(ctx: &RpcContext) -> Vec<PathBuf> {
+    let state = ctx.state.read().await;
+    std::iter::once(state.project_dir())
+        .chain(state.options().allowed_roots.iter().map(PathBuf::as_path))
+        .filter_map(|dir| file_utils::canonicalize_existing(dir).ok())
+        .collect()
+}
+
+// The canonical path of a root, if it lies in one of the allowed directories
+fn inside_allowed(path: &Path, allowed: &[PathBuf]) -> Option<PathBuf> {
+    let path = file_utils::canonicalize_existing(path).ok()?;
+    allowed
+        .iter()
+        .any(|dir| path.starts_with(dir))
+        .then_some(path)
+}
+
+// Name a project after its directory
+fn directory_name(path: &Path) -> String {
+    path.file_name()
+        .map(|name| name.to_string_lossy().into_owned())
+        .unwrap_or_else(|| path.display().to_string())
+}
@@ -0,0 +1,722 @@
+// JSON-RPC dispatcher shared by all transports
+
+use futures_util::future::join_all;
+use log::{debug, error, info};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{json, Value};
+use std::path::PathBuf;
+use std::sync::Arc;
+use tokio::sync::{mpsc, RwLock};
+
+use crate::api::auth::{Scope, Scopes};
+use crate::api::build::BuildRequest;
+use crate::api::clients::ClientHelloRequest;
+use crate::api::completion::CompleteRequest;
+use crate::api::components::{
+    ComponentIdRequest, CreateComponentRequest, UpdateComponentByIdRequest,
+};
+use crate::api::documentation::{DocExampleRequest, DocQueryRequest};
+use crate::api::events::{SubscribeEventsRequest, UnsubscribeEventsRequest};
+use crate::api::jsonrpc::{
+    CancelParams, JsonRpcError, JsonRpcNotification, JsonRpcReply, JsonRpcRequest, JsonRpcResponse,
+    JSONRPC_VERSION,
+};
+use crate::api::logging::SetLevelRequest;
+use crate::api::mcp::{CallToolRequest, CancelledParams, InitializeRequest};
+use crate::api::projects::{CreateProjectRequest, UpdateProjectRequest};
+use crate::api::prompts::GetPromptRequest;
+use crate::api::resources::ResourceUriRequest;
+use crate::api::testing::TestRequest;
+use crate::handlers::{
+    build, clients, completion, components, documentation, events, logging, mcp, projects, prompts,
+    resources, roots, testing,
+};
+use crate::server::ServerState;
+use crate::utils;
+use crate::utils::auth;
+use crate::utils::error::McpError;
+use crate::utils::event_bus::EventBus;
+use crate::utils::file_utils::DenyList;
+use crate::utils::progress::ProgressReporter;
+
+/// Everything a JSON-RPC method needs to serve a request
+#[derive(Clone)]
+pub struct RpcContext {
+    /// ID of the client that sent the request
+    pub client_id: String,
+    /// Shared server state
+    pub state: Arc<RwLock<ServerState>>,
+    /// Broadcast channel for events
+    pub event_tx: EventBus,
+    /// The project directory
+    pub project_dir: PathBuf,
+    /// Channel for notifications addressed to the requesting client
+    pub outbound: mpsc::UnboundedSender<Value>,
+    /// Progress token from the request's `_meta`, if the client asked for progress
+    pub progress_token: Option<Value>,
+    /// Scopes the credentials sent with the request grant; the client's own
+    /// scopes are narrowed to these
+    pub granted: Scopes,
+}
+
+impl RpcContext {
+    /// The paths in the project clients can't read or write
+    pub async fn deny_list(&self) -> DenyList {
+        self.state.read().await.deny_list().clone()
+    }
+
+    /// Create a progress reporter for an operation run by this request
+    pub fn progress(&self, operation: &str) -> ProgressReporter {
+        let reporter = ProgressReporter::new(operation, self.event_tx.clone());
+        match &self.progress_token {
+            Some(token) => reporter.with_client(token.clone(), self.outbound.clone()),
+            None => reporter,
+        }
+    }
+
+    /// Send a request to the requesting client and wait for its result
+    pub async fn request_client<P: Serialize, T: DeserializeOwned>(
+        &self,
+        method: &str,
+        params: P,
+    ) -> Result<T, McpError> {
+        let (id, response) =
+            self.state
+                .write()
+                .await
+                .send_request(&self.client_id, method, json!(params))?;
+        let mut pending = PendingClientRequest {
+            ctx: self.clone(),
+            id,
+            reason: "Request cancelled",
+            settled: false,
+        };
+        let timeout = self.state.read().await.options().client_request_timeout;
+
+        let response = if timeout.is_zero() {
+            response.await
+        } else {
+            match tokio::time::timeout(timeout, response).await {
+                Ok(response) => response,
+                Err(_) => {
+                    pending.reason = "Timed out";
+                    return Err(McpError::ClientError(format!("{} timed out", method)));
+                }
+            }
+        };
+        pending.settled = true;
+
+        let response =
+            response.map_err(|_| McpError::ClientError("Client disconnected".to_string()))?;
+        if let Some(error) = response.error {
+            return Err(McpError::ClientError(format!(
+                "{} failed: {}",
+                method, error.message
+            )));
+        }
+        serde_json::from_value(response.result.unwrap_or(Value::Null))
+            .map_err(|e| McpError::ClientError(format!("Invalid {} result: {}", method, e)))
+    }
+}
+
+// A request sent to the client, abandoned if dropped before it settles
+//
+// Dropped when it times out, or when the request waiting on it is cancelled
+// and its future dropped. The client is told it can stop working on it.
+struct PendingClientRequest {
+    ctx: RpcContext,
+    id: Value,
+    reason: &'static str,
+    settled: bool,
+}
+
+impl Drop for PendingClientRequest {
+    fn drop(&mut self) {
+        if self.settled {
+            return;
+        }
+
+        let notification = JsonRpcNotification::new(
+            "notifications/cancelled",
+            json!({ "requestId": self.id, "reason": self.reason }),
+        );
+        let _ = self.ctx.outbound.send(json!(notification));
+
+        // Dropping can't wait for the state lock
+        let state = self.ctx.state.clone();
+        let client_id = self.ctx.client_id.clone();
+        let id = self.id.take();
+        tokio::spawn(async move {
+            state.write().await.abandon_request(&client_id, &id);
+        });
+    }
+}
+
+/// Handle a raw JSON-RPC frame, returning the reply to send back (if any)
+pub async fn handle_message(ctx: &RpcContext, text: &str) -> Option<JsonRpcReply> {
+    // Records about the message, its raw text included, only go to its client
+    utils::logging::scope(&ctx.client_id, None, receive_message(ctx, text)).await
+}
+
+// Parse a raw JSON-RPC frame and handle what it holds
+async fn receive_message(ctx: &RpcContext, text: &str) -> Option<JsonRpcReply> {
+    let message: Value = match serde_json::from_str(text) {
+        Ok(message) => message,
+        Err(e) => {
+            error!("Failed to parse JSON-RPC message: {}", e);
+            return Some(JsonRpcReply::Single(JsonRpcResponse::failure(
+                Value::Null,
+                JsonRpcError::parse_error(e.to_string()),
+            )));
+        }
+    };
+
+    debug!(
+        "Received JSON-RPC message from client {}: {:?}",
+        ctx.client_id, message
+    );
+
+    match message {
+        Value::Array(batch) => handle_batch(ctx, batch).await,
+        message => handle_request(ctx, message).await.map(JsonRpcReply::Single),
+    }
+}
+
+// Handle a batch, running its requests concurrently
+async fn handle_batch(ctx: &RpcContext, batch: Vec<Value>) -> Option<JsonRpcReply> {
+    if batch.is_empty() {
+        return Some(JsonRpcReply::Single(JsonRpcResponse::failure(
+            Value::Null,
+            JsonRpcError::invalid_request("batch must not be empty"),
+        )));
+    }
+
+    let responses: Vec<JsonRpcResponse> = join_all(
+        batch
+            .into_iter()
+            .map(|message| handle_request(ctx, message)),
+    )
+    .await
+    .into_iter()
+    .flatten()
+    .collect();
+
+    // A batch of notifications gets no reply at all
+    if responses.is_empty() {
+        None
+    } else {
+        Some(JsonRpcReply::Batch(responses))
+    }
+}
+
+// Handle a single parsed JSON-RPC request or notification
+async fn handle_request(ctx: &RpcContext, message: Value) -> Option<JsonRpcResponse> {
+    // Responses answer requests the server sent, and never get a reply
+    if is_response(&message) {
+        complete_request(ctx, message).await;
+        return None;
+    }
+
+    // Notifications are requests without an `id` member and never get a reply
+    let is_notification = message.is_object() && message.get("id").is_none();
+
+    let id = match message.get("id") {
+        Some(id @ (Value::String(_) | Value::Number(_) | Value::Null)) => id.clone(),
+        Some(_) => {
+            return Some(JsonRpcResponse::failure(
+                Value::Null,
+                JsonRpcError::invalid_request("id must be a string, number or null"),
+            ))
+        }
+        None => Value::Null,
+    };
+
+    let request: JsonRpcRequest = match serde_json::from_value(message) {
+        Ok(request) => request,
+        Err(e) => {
+            return Some(JsonRpcResponse::failure(
+                id,
+                JsonRpcError::invalid_request(e.to_string()),
+            ))
+        }
+    };
+
+    if request.jsonrpc != JSONRPC_VERSION {
+        return Some(JsonRpcResponse::failure(
+            id,
+            JsonRpcError::invalid_request("jsonrpc must be \"2.0\""),
+        ));
+    }
+
+    // Progress for this request goes to the token the client picked, if any
+    let progress_token = request
+        .params
+        .as_ref()
+        .and_then(|params| params.get("_meta"))
+        .and_then(|meta| meta.get("progressToken"))
+        .cloned();
+    let ctx = RpcContext {
+        progress_token,
+        ..ctx.clone()
+    };
+
+    if is_notification {
+        let result = dispatch(&ctx, &request.method, request.params).await;
+        if let Err(e) = result {
+            debug!("Notification {} failed: {}", request.method, e.message);
+        }
+        return None;
+    }
+
+    let result = dispatch_cancellable(&ctx, &id, &request.method, request.params).await;
+
+    Some(match result {
+        Ok(result) => JsonRpcResponse::success(id, result),
+        Err(e) => JsonRpcResponse::failure(id, e),
+    })
+}
+
+// Run a request while tracking it, so the client can cancel it midway
+async fn dispatch_cancellable(
+    ctx: &RpcContext,
+    id: &Value,
+    method: &str,
+    params: Option<Value>,
+) -> Result<Value, JsonRpcError> {
+    let token = ctx.state.write().await.start_request(&ctx.client_id, id);
+
+    // Cancelling drops the handler future, so it stops at whatever it was
+    // waiting on, such as the client's answer to a server request, which is
+    // then cancelled in turn
+    let result = tokio::select! {
+        result = utils::logging::scope(&ctx.client_id, Some(id), dispatch(ctx, method, params)) => {
+            result
+        }
+        _ = token.cancelled() => Err(JsonRpcError::request_cancelled()),
+    };
+
+    ctx.state.write().await.finish_request(&ctx.client_id, id);
+
+    result
+}
+
+// Whether a message is a response rather than a request or notification
+fn is_response(message: &Value) -> bool {
+    message.get("method").is_none()
+        && (message.get("result").is_some() || message.get("error").is_some())
+}
+
+// Pass a client's response on to the request waiting for it
+async fn complete_request(ctx: &RpcContext, message: Value) {
+    let response: JsonRpcResponse = match serde_json::from_value(message) {
+        Ok(response) => response,
+        Err(e) => {
+            debug!(
+                "Ignoring malformed response from client {}: {}",
+                ctx.client_id, e
+            );
+            return;
+        }
+    };
+
+    let id = response.id.clone();
+    if !ctx
+        .state
+        .write()
+        .await
+        .complete_request(&ctx.client_id, response)
+    {
+        debug!("Client {} answered unknown request {}", ctx.client_id, id);
+    }
+}
+
+// Cancel one of the requesting client's in-flight requests
+async fn cancel_request(ctx: &RpcContext, id: &Value, reason: Option<&str>) {
+    if ctx.state.write().await.cancel_request(&ctx.client_id, id) {
+        info!(
+            "Client {} cancelled request {}: {}",
+            ctx.client_id,
+            id,
+            reason.unwrap_or("no reason given")
+        );
+    } else {
+        // The request may simply have finished already
+        debug!("Client {} cancelled unknown request {}", ctx.client_id, id);
+    }
+}
+
+/// Every method `dispatch` handles; others are answered with "method not found"
+pub const METHODS: &[&str] = &[
+    "initialize",
+    "notifications/initialized",
+    "notifications/roots/list_changed",
+    "ping",
+    "client.hello",
+    "notifications/cancelled",
+    "$/cancelRequest",
+    "logging/setLevel",
+    "tools/list",
+    "tools/call",
+    "prompts/list",
+    "prompts/get",
+    "completion/complete",
+    "resources/list",
+    "resources/templates/list",
+    "resources/read",
+    "resources/subscribe",
+    "resources/unsubscribe",
+    "events.subscribe",
+    "events.unsubscribe",
+    "component.list",
+    "component.create",
+    "component.get",
+    "component.update",
+    "component.delete",
+    "component.analyze",
+    "component.describe",
+    "project.list",
+    "project.info",
+    "project.create",
+    "project.update",
+    "build.compile",
+    "build.errors",
+    "test.run",
+    "test.results",
+    "docs.query",
+    "docs.examples",
+];
+
+// The scope a method needs, if any
+//
+// Methods that aren't listed need `admin`, so new ones are locked down until
+// they are given a scope.
+fn required_scope(method: &str) -> Option<Scope> {
+    listed_scope(method).unwrap_or(Some(Scope::Admin))
+}
+
+// The scope listed for a method: `Some(None)` if it needs none, and `None` if
+// it isn't listed at all
+fn listed_scope(method: &str) -> Option<Option<Scope>> {
+    let scope = match method {
+        "initialize"
+        | "notifications/initialized"
+        | "notifications/roots/list_changed"
+        | "ping"
+        | "client.hello"
+        | "notifications/cancelled"
+        | "$/cancelRequest" => None,
+
+        // Tools need the scope of the method they run, checked when it runs
+        "logging/setLevel"
+        | "tools/list"
+        | "tools/call"
+        | "prompts/list"
+        | "prompts/get"
+        | "completion/complete"
+        | "resources/list"
+        | "resources/templates/list"
+        | "resources/read"
+        | "resources/subscribe"
+        | "resources/unsubscribe"
+        | "events.subscribe"
+        | "events.unsubscribe"
+        | "component.list"
+        | "component.get"
+        | "component.analyze"
+        | "component.describe"
+        | "project.list"
+        | "project.info"
+        | "build.errors"
+        | "test.results"
+        | "docs.query"
+        | "docs.examples" => Some(Scope::Read),
+
+        "component.create" | "component.update" | "component.delete" | "project.create"
+        | "project.update" => Some(Scope::Write),
+
+        "build.compile" => Some(Scope::Build),
+        "test.run" => Some(Scope::Test),
+
+        _ => return None,
+    };
+    Some(scope)
+}
+
+// Deserialize method parameters, mapping failures to an invalid params error
+fn parse_params<T: DeserializeOwned>(params: Option<Value>) -> Result<T, JsonRpcError> {
+    serde_json::from_value(params.unwrap_or(Value::Null))
+        .map_err(|e| JsonRpcError::invalid_params(e.to_string()))
+}
+
+/// Route a method call to the matching handler
+pub async fn dispatch(
+    ctx: &RpcContext,
+    method: &str,
+    params: Option<Value>,
+) -> Result<Value, JsonRpcError> {
+    if !METHODS.contains(&method) {
+        return Err(JsonRpcError::method_not_found(method));
+    }
+
+    // Refuse calls the client may not make before looking at the filesystem
+    if let Some(scope) = required_scope(method) {
+        let scopes = ctx.state.read().await.client_scopes(&ctx.client_id);
+        auth::require(&scopes.intersection(&ctx.granted), scope)?;
+    }
+
+    // Work on the project the request addresses, one of the client's roots
+    let project_dir = roots::resolve(ctx, &ctx.project_dir, params.as_ref()).await?;
+    let ctx = &RpcContext {
+        project_dir,
+        ..ctx.clone()
+    };
+
+    match method {
+        // MCP lifecycle
+        "initialize" => {
+            let request: InitializeRequest = parse_params(params)?;
+            let result = mcp::initialize(ctx, request).await?;
+            Ok(json!(result))
+        }
+        "notifications/initialized" => {
+            debug!("Client {} finished initialization", ctx.client_id);
+            roots::refresh_in_background(ctx).await;
+            Ok(Value::Null)
+        }
+        "notifications/roots/list_changed" => {
+            roots::refresh_in_background(ctx).await;
+            Ok(Value::Null)
+        }
+        "ping" => Ok(json!({})),
+        "client.hello" => {
+            let request: ClientHelloRequest = parse_params(params)?;
+            let result = clients::hello(ctx, request).await?;
+            Ok(json!(result))
+        }
+        "notifications/cancelled" => {
+            let request: CancelledParams = parse_params(params)?;
+            cancel_request(ctx, &request.request_id, request.reason.as_deref()).await;
+            Ok(Value::Null)
+        }
+        "$/cancelRequest" => {
+            let request: CancelParams = parse_params(params)?;
+            cancel_request(ctx, &request.id, None).await;
+            Ok(Value::Null)
+        }
+
+        "logging/setLevel" => {
+            let request: SetLevelRequest = parse_params(params)?;
+            logging::set_level(ctx, request).await?;
+            Ok(json!({}))
+        }
+
+        // MCP tools
+        "tools/list" => {
+            let result = mcp::list_tools().await?;
+            Ok(json!(result))
+        }
+        "tools/call" => {
+            let request: CallToolRequest = parse_params(params)?;
+            let result = mcp::call_tool(ctx, request).await?;
+            Ok(json!(result))
+        }
+        "prompts/list" => {
+            let result = prompts::list(&ctx.project_dir).await?;
+            Ok(json!(result))
+        }
+        "prompts/get" => {
+            let request: GetPromptRequest = parse_params(params)?;
+            let result = prompts::get(&ctx.project_dir, &ctx.state, request).await?;
+            Ok(json!(result))
+        }
+        "completion/complete" => {
+            let request: CompleteRequest = parse_params(params)?;
+            let result = completion::complete(ctx, request).await?;
+            Ok(json!(result))
+        }
+        "resources/list" => {
+            let result = resources::list(&ctx.project_dir, &ctx.deny_list().await).await?;
+            Ok(json!(result))
+        }
+        "resources/templates/list" => {
+            let result = resources::templates().await?;
+            Ok(json!(result))
+        }
+        "resources/read" => {
+            let request: ResourceUriRequest = parse_params(params)?;
+            let result =
+                resources::read(&ctx.project_dir, &ctx.deny_list().await, &request.uri).await?;
+            Ok(json!(result))
+        }
+        "resources/subscribe" => {
+            let request: ResourceUriRequest = parse_params(params)?;
+            resources::subscribe(ctx, &request.uri).await?;
+            Ok(json!({}))
+        }
+        "resources/unsubscribe" => {
+            let request: ResourceUriRequest = parse_params(params)?;
+            resources::unsubscribe(ctx, &request.uri).await?;
+            Ok(json!({}))
+        }
+
+        // Event subscriptions
+        "events.subscribe" => {
+            let request: SubscribeEventsRequest = parse_params(params)?;
+            let result = events::subscribe(ctx, request).await?;
+            Ok(json!(result))
+        }
+        "events.unsubscribe" => {
+            let request: Option<UnsubscribeEventsRequest> = parse_params(params)?;
+            events::unsubscribe(ctx, request.unwrap_or_default()).await?;
+            Ok(json!({}))
+        }
+
+        // Component management
+        "component.list" => {
+            let components = components::list(&ctx.project_dir).await?;
+            Ok(json!({ "components": components }))
+        }
+        "component.create" => {
+            let request: CreateComponentRequest = parse_params(params)?;
+            let component =
+                components::create(&ctx.project_dir, &ctx.deny_list().await, request).await?;
+            Ok(json!({ "component": component }))
+        }
+        "component.get" => {
+            let request: ComponentIdRequest = parse_params(params)?;
+            let component =
+                components::get(&ctx.project_dir, &ctx.deny_list().await, &request.id).await?;
+            Ok(json!({ "component": component }))
+        }
+        "component.update" => {
+            let request: UpdateComponentByIdRequest = parse_params(params)?;
+            let component = components::update(
+                &ctx.project_dir,
+                &ctx.deny_list().await,
+                &request.id,
+                request.update,
+            )
+            .await?;
+            Ok(json!({ "component": component }))
+        }
+        "component.delete" => {
+            let request: ComponentIdRequest = parse_params(params)?;
+            components::delete(&ctx.project_dir, &ctx.deny_list().await, &request.id).await?;
+            Ok(json!({
+                "success": true,
+                "message": format!("Component {} deleted", request.id)
+            }))
+        }
+        "component.analyze" => {
+            let request: ComponentIdRequest = parse_params(params)?;
+            let analysis =
+                components::analyze(&ctx.project_dir, &ctx.deny_list().await, &request.id).await?;
+            Ok(json!({ "analysis": analysis }))
+        }
+
+        "component.describe" => {
+            let request: ComponentIdRequest = parse_params(params)?;
+            let description = components::describe(ctx, &request.id).await?;
+            Ok(json!({ "description": description }))
+        }
+
+        // Project management
+        "project.list" => {
+            let projects = roots::list(ctx, &ctx.project_dir).await;
+            Ok(json!({ "projects": projects }))
+        }
+        "project.info" => {
+            let project = projects::info(&ctx.project_dir).await?;
+            Ok(json!({ "project": project }))
+        }
+        "project.create" => {
+            let request: CreateProjectRequest = parse_params(params)?;
+            let project =
+                projects::create(&ctx.project_dir, &ctx.deny_list().await, request).await?;
+            Ok(json!({ "project": project }))
+        }
+        "project.update" => {
+            let request: UpdateProjectRequest = parse_params(params)?;
+            let project = projects::update(&ctx.project_dir, request).await?;
+            Ok(json!({ "project": project }))
+        }
+
+        // Build and compilation
+        "build.compile" => {
+            let request: BuildRequest = parse_params(params)?;
+            let result = build::compile(
+                &ctx.project_dir,
+                &ctx.state,
+                &ctx.progress("build"),
+                request,
+            )
+            .await?;
+            Ok(json!({ "result": result }))
+        }
+        "build.errors" => {
+            let errors = build::errors(&ctx.state).await?;
+            Ok(json!({ "errors": errors }))
+        }
+
+        // Testing
+        "test.run" => {
+            let request: TestRequest = parse_params(params)?;
+            let result =
+                testing::run(&ctx.project_dir, &ctx.state, &ctx.progress("test"), request).await?;
+            Ok(json!({ "result": result }))
+        }
+        "test.results" => {
+            let result = testing::results(&ctx.state).await?;
+            Ok(json!({ "result": result }))
+        }
+
+        // Documentation
+        "docs.query" => {
+            let request: DocQueryRequest = parse_params(params)?;
+            let items = documentation::query(request).await?;
+            Ok(json!({ "items": items }))
+        }
+        "docs.examples" => {
+            let request: DocExampleRequest = parse_params(params)?;
+            let examples = documentation::examples(request).await?;
+            Ok(json!({ "examples": examples }))
+        }
+
+        _ => Err(JsonRpcError::method_not_found(method)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::schema;
+
+    #[test]
+    fn every_method_has_a_scope() {
+        for method in METHODS {
+            assert!(listed_scope(method).is_some(), "{} has no scope", method);
+        }
+    }
+
+    #[test]
+    fn every_orbit_method_has_a_schema() {
+        // MCP methods, with names like `initialize` or `tools/call`, follow
+        // the MCP specification
+        for method in METHODS.iter().filter(|method| method.contains('.')) {
+            assert!(
+                schema::rpc_method(method).is_some(),
+                "{} has no schema",
+                method
+            );
+        }
+
+        for method in schema::RPC_METHODS {
+            assert!(
+                METHODS.contains(&method.name),
+                "{} isn't dispatched",
+                method.name
+            );
+        }
+    }
+}
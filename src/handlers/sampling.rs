@@ -0,0 +1,29 @@
+// Sampling handler implementations (asking the client's model)
+
+use crate::api::mcp::Content;
+use crate::api::sampling::{CreateMessageRequest, CreateMessageResult};
+use crate::handlers::rpc::RpcContext;
+use crate::utils::error::McpError;
+
+/// Ask the requesting client's model to generate a message
+///
+/// Only works for clients that declared the `sampling` capability.
+pub async fn create_message(
+    ctx: &RpcContext,
+    request: CreateMessageRequest,
+) -> Result<CreateMessageResult, McpError> {
+    ctx.request_client("sampling/createMessage", request).await
+}
+
+/// Ask the requesting client's model for a text answer
+pub async fn create_text(
+    ctx: &RpcContext,
+    request: CreateMessageRequest,
+) -> Result<String, McpError> {
+    match create_message(ctx, request).await?.content {
+        Content::Text { text } => Ok(text),
+        _ => Err(McpError::ClientError(
+            "Expected text from the client's model".to_string(),
+        )),
+    }
+}
@@ -1,27 +1,34 @@
 // Testing handler implementations
 
-use anyhow::{Context, Result};
 use axum::{
     extract::Extension,
     http::StatusCode,
     response::{IntoResponse, Json},
 };
-use serde_json::{json, Value};
-use std::path::PathBuf;
+use serde_json::json;
+use std::path::{Path, PathBuf};
 use std::sync::Arc;
 use std::time::Duration;
 use tokio::sync::RwLock;
 
+use crate::api::auth::Scope;
 use crate::api::testing::{TestRequest, TestResult};
 use crate::server::ServerState;
+use crate::utils::auth::Granted;
+use crate::utils::error::McpError;
+use crate::utils::event_bus::EventBus;
+use crate::utils::file_utils;
+use crate::utils::progress::ProgressReporter;
 
-// Run tests
-pub async fn run_tests(
-    Extension(project_dir): Extension<PathBuf>,
-    Json(request): Json<TestRequest>,
-) -> impl IntoResponse {
+// Run the project tests
+pub async fn run(
+    _project_dir: &Path,
+    state: &Arc<RwLock<ServerState>>,
+    progress: &ProgressReporter,
+    _request: TestRequest,
+) -> Result<TestResult, McpError> {
     // TODO: Implement test running logic
-    // For now, return a mock response
+    // For now, report each test as it completes and return a mock response
     let result = TestResult {
         tests_run: 10,
         tests_passed: 9,
@@ -32,5 +39,79 @@ pub async fn run_tests(
         coverage: None,
     };
 
-    (StatusCode::OK, Json(json!({ "result": result })))
+    let total = u64::from(result.tests_run);
+    progress.report(0, Some(total), "Running tests");
+    for done in 1..=total {
+        progress.report(
+            done,
+            Some(total),
+            format!("Completed test {} of {}", done, total),
+        );
+    }
+
+    state.write().await.set_last_test(result.clone());
+
+    Ok(result)
+}
+
+// Find the names of the tests in the project's Rust sources
+//
+// A test is a function marked with an attribute such as `#[test]` or
+// `#[tokio::test]`. Its name is what `test.run` filters match against.
+pub async fn discover(project_dir: &Path) -> Vec<String> {
+    let files = file_utils::find_component_files(project_dir, "rs")
+        .await
+        .unwrap_or_default();
+
+    let mut names = Vec::new();
+    for file in files {
+        let Ok(source) = tokio::fs::read_to_string(&file).await else {
+            continue;
+        };
+
+        let mut marked = false;
+        for line in source.lines().map(str::trim) {
+            if line.starts_with("#[") {
+                marked |= line.contains("test]") || line.contains("test(");
+            } else if marked {
+                if let Some(name) = function_name(line) {
+                    names.push(name.to_string());
+                }
+                marked = false;
+            }
+        }
+    }
+
+    names.sort();
+    names.dedup();
+    names
+}
+
+// The name of the function a line declares, if it declares one
+fn function_name(line: &str) -> Option<&str> {
+    let (_, rest) = line.split_once("fn ")?;
+    let end = rest
+        .find(|c: char| !(c.is_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    Some(&rest[..end]).filter(|name| !name.is_empty())
+}
+
+// Get the result of the most recent test run
+pub async fn results(state: &Arc<RwLock<ServerState>>) -> Result<Option<TestResult>, McpError> {
+    Ok(state.read().await.last_test().cloned())
+}
+
+// Run tests
+pub async fn run_tests(
+    Extension(granted): Extension<Granted>,
+    Extension(project_dir): Extension<PathBuf>,
+    Extension(state): Extension<Arc<RwLock<ServerState>>>,
+    Extension(event_tx): Extension<EventBus>,
+    Json(request): Json<TestRequest>,
+) -> Result<impl IntoResponse, McpError> {
+    granted.require(Scope::Test)?;
+    let progress = ProgressReporter::new("test", event_tx);
+    let result = run(&project_dir, &state, &progress, request).await?;
+
+    Ok((StatusCode::OK, Json(json!({ "result": result }))))
 }
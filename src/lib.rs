@@ -1,8 +1,10 @@
 // Library exports for the orbit-mcp crate
 
 pub mod api;
+pub mod client;
 pub mod handlers;
 pub mod server;
+pub mod transport;
 pub mod utils;
 
 // Re-export core types
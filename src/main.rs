@@ -1,9 +1,18 @@
 // Main entry point for the MCP server
 
-use anyhow::Result;
-use clap::Parser;
-use log::info;
-use orbit_mcp::server::McpServer;
+use anyhow::{Context, Result};
+use clap::{Parser, ValueEnum};
+use log::{info, warn};
+use orbit_mcp::api::auth::Scopes;
+use orbit_mcp::server::{
+    McpServer, ServerOptions, DEFAULT_CLIENT_REQUEST_TIMEOUT_SECS,
+    DEFAULT_HTTP_SESSION_TIMEOUT_SECS, DEFAULT_MAX_HTTP_SESSIONS, DEFAULT_PING_INTERVAL_SECS,
+    DEFAULT_PONG_TIMEOUT_SECS, DEFAULT_SESSION_GRACE_SECS,
+};
+use orbit_mcp::utils::tls::{self, TlsConfig};
+use orbit_mcp::utils::{auth, logging, origin};
+use std::collections::HashMap;
+use std::time::Duration;
 
 /// Model Context Protocol (MCP) server for the Orbit Framework
 #[derive(Parser, Debug)]
@@ -14,18 +23,125 @@ struct CliArgs {
     port: u16,
 
     /// Host address to bind to
-    #[arg(short, long, default_value = "127.0.0.1")]
+    #[arg(long, default_value = "127.0.0.1")]
     host: String,
 
     /// Path to the Orbit project
-    #[arg(short, long)]
+    #[arg(long)]
     project: Option<std::path::PathBuf>,
+
+    /// Transport to serve the MCP protocol over
+    #[arg(long, value_enum, default_value_t = Transport::Http)]
+    transport: Transport,
+
+    /// Seconds a dropped WebSocket session can be resumed (0 to disable)
+    #[arg(long, default_value_t = DEFAULT_SESSION_GRACE_SECS)]
+    session_grace: u64,
+
+    /// Seconds between keepalive pings to WebSocket clients (0 to disable)
+    #[arg(long, default_value_t = DEFAULT_PING_INTERVAL_SECS)]
+    ping_interval: u64,
+
+    /// Seconds a WebSocket client has to answer a ping (0 to wait forever)
+    #[arg(long, default_value_t = DEFAULT_PONG_TIMEOUT_SECS)]
+    pong_timeout: u64,
+
+    /// Seconds a WebSocket client may send nothing before it's dropped (0 to disable)
+    #[arg(long, default_value_t = 0)]
+    idle_timeout: u64,
+
+    /// Seconds a client has to answer a request from the server, such as sampling (0 to wait forever)
+    #[arg(long, default_value_t = DEFAULT_CLIENT_REQUEST_TIMEOUT_SECS)]
+    client_request_timeout: u64,
+
+    /// Seconds a Streamable HTTP session may go unused before it's closed (0 to keep sessions until deleted)
+    #[arg(long, default_value_t = DEFAULT_HTTP_SESSION_TIMEOUT_SECS)]
+    http_session_timeout: u64,
+
+    /// Most Streamable HTTP sessions open at once (0 for no limit)
+    #[arg(long, default_value_t = DEFAULT_MAX_HTTP_SESSIONS)]
+    max_http_sessions: usize,
+
+    /// File to write the bearer token clients must present to (default: ~/.orbit/mcp-<port>.token)
+    #[arg(long)]
+    token_file: Option<std::path::PathBuf>,
+
+    /// Accept HTTP and WebSocket requests without a token
+    #[arg(long)]
+    no_auth: bool,
+
+    /// Generate another token granting only some scopes, written next to the token file as <stem>-<NAME>.token (repeatable, e.g. agent=read,test)
+    #[arg(long, value_name = "NAME=SCOPES", value_parser = parse_named_scopes, conflicts_with = "no_auth")]
+    scoped_token: Vec<(String, Scopes)>,
+
+    /// Narrow clients that call themselves NAME to some scopes; the name is self-declared, so use --scoped-token to enforce limits (repeatable, e.g. my-agent=read)
+    #[arg(long, value_name = "NAME=SCOPES", value_parser = parse_named_scopes)]
+    client_scopes: Vec<(String, Scopes)>,
+
+    /// Reject every operation that changes the project or the server
+    #[arg(long)]
+    read_only: bool,
+
+    /// Keep clients away from more paths in the project, on top of .git, target and secrets files (repeatable glob)
+    #[arg(long, value_name = "PATTERN")]
+    deny_path: Vec<String>,
+
+    /// Accept client roots inside DIR too, not only inside the project (repeatable)
+    #[arg(long, value_name = "DIR")]
+    allow_root: Vec<std::path::PathBuf>,
+
+    /// Let browser pages on ORIGIN call the server, e.g. http://localhost:5173 (repeatable, * for any)
+    #[arg(long, value_name = "ORIGIN")]
+    allow_origin: Vec<String>,
+
+    /// Let clients address the server by HOST, on top of localhost and the bind address (repeatable, * for any)
+    #[arg(long, value_name = "HOST")]
+    allow_host: Vec<String>,
+
+    /// HTTP methods allowed origins may use (default: GET, POST, PUT, DELETE)
+    #[arg(long, value_name = "METHOD", value_delimiter = ',')]
+    cors_methods: Vec<String>,
+
+    /// Request headers allowed origins may send (default: authorization, content-type, accept, mcp-session-id, last-event-id)
+    #[arg(long, value_name = "HEADER", value_delimiter = ',')]
+    cors_headers: Vec<String>,
+
+    /// Serve HTTPS and WSS with the certificate in this PEM file
+    #[arg(long, value_name = "FILE", requires = "tls_key")]
+    tls_cert: Option<std::path::PathBuf>,
+
+    /// Private key for --tls-cert, as a PEM file
+    #[arg(long, value_name = "FILE", requires = "tls_cert")]
+    tls_key: Option<std::path::PathBuf>,
+
+    /// Serve HTTPS and WSS with a self-signed certificate, cached in ~/.orbit/tls and regenerated when the names change
+    #[arg(long, conflicts_with = "tls_cert")]
+    tls_self_signed: bool,
+}
+
+// Parse a `NAME=SCOPES` pair, such as `agent=read,test`
+fn parse_named_scopes(value: &str) -> Result<(String, Scopes), String> {
+    let (name, scopes) = value
+        .split_once('=')
+        .ok_or_else(|| format!("expected NAME=SCOPES, got {:?}", value))?;
+    Ok((name.to_string(), scopes.parse()?))
+}
+
+/// Transport used to talk to clients
+#[derive(Clone, Copy, Debug, PartialEq, Eq, ValueEnum)]
+enum Transport {
+    /// HTTP and WebSocket listener
+    Http,
+    /// Newline-delimited JSON-RPC over stdin/stdout (for MCP hosts that spawn the server)
+    Stdio,
 }
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    // Initialize logging
-    env_logger::init();
+    // Initialize logging; always log to stderr so stdout stays free for stdio frames
+    let mut logger = env_logger::Builder::from_default_env();
+    logger.target(env_logger::Target::Stderr);
+    logging::init(logger)?;
 
     // Parse command line arguments
     let args = CliArgs::parse();
@@ -35,14 +151,115 @@ async fn main() -> Result<()> {
         .project
         .unwrap_or_else(|| std::env::current_dir().unwrap_or_default());
 
+    // Generate the tokens HTTP clients must present; stdio clients spawned the
+    // server themselves and need none
+    let mut scoped_tokens = HashMap::new();
+    let auth_token = if args.transport == Transport::Http && !args.no_auth {
+        let token = auth::generate_token();
+        let token_file = args
+            .token_file
+            .unwrap_or_else(|| auth::default_token_path(args.port));
+        auth::write_token_file(&token_file, &token)
+            .with_context(|| format!("Failed to write token file {:?}", token_file))?;
+        info!("Clients must present the token in {:?}", token_file);
+
+        for (name, scopes) in args.scoped_token {
+            let token = auth::generate_token();
+            let path = auth::scoped_token_path(&token_file, &name);
+            auth::write_token_file(&path, &token)
+                .with_context(|| format!("Failed to write token file {:?}", path))?;
+            info!("Token in {:?} grants {}", path, scopes);
+            scoped_tokens.insert(token, scopes);
+        }
+        Some(token)
+    } else {
+        if args.no_auth {
+            warn!("Authentication is disabled; anyone who can reach the server can use it");
+        }
+        None
+    };
+
     // Create and start the MCP server
-    info!(
-        "Starting Orbit MCP server at {}:{} for project at {:?}",
-        args.host, args.port, project_dir
-    );
+    let defaults = ServerOptions::default();
+    let mut denied_paths = defaults.denied_paths;
+    denied_paths.extend(args.deny_path);
+    let mut allowed_hosts = defaults.allowed_hosts;
+    allowed_hosts.extend(args.allow_host);
+
+    let tls = match (args.tls_cert, args.tls_key) {
+        (Some(cert), Some(key)) => Some(TlsConfig::new(cert, key)),
+        _ if args.tls_self_signed => {
+            // Cover every name clients may address the server by
+            let mut names: Vec<String> = allowed_hosts
+                .iter()
+                .chain(std::iter::once(&args.host))
+                .filter(|host| !matches!(host.as_str(), origin::ANY | "0.0.0.0" | "::"))
+                .map(|host| host.trim_matches(|c| c == '[' || c == ']').to_string())
+                .collect();
+            names.sort();
+            names.dedup();
+            let dir = tls::default_self_signed_dir();
+            let config = TlsConfig::self_signed(&dir, &names)?;
+            info!("Using the self-signed certificate in {:?}", dir);
+            Some(config)
+        }
+        _ => None,
+    };
+    let options = ServerOptions {
+        session_grace: Duration::from_secs(args.session_grace),
+        ping_interval: Duration::from_secs(args.ping_interval),
+        pong_timeout: Duration::from_secs(args.pong_timeout),
+        idle_timeout: Duration::from_secs(args.idle_timeout),
+        client_request_timeout: Duration::from_secs(args.client_request_timeout),
+        http_session_timeout: Duration::from_secs(args.http_session_timeout),
+        max_http_sessions: args.max_http_sessions,
+        auth_token,
+        scoped_tokens,
+        client_scopes: args.client_scopes.into_iter().collect(),
+        read_only: args.read_only,
+        denied_paths,
+        allowed_roots: args.allow_root,
+        allowed_origins: args.allow_origin,
+        allowed_hosts,
+        allowed_methods: if args.cors_methods.is_empty() {
+            defaults.allowed_methods
+        } else {
+            args.cors_methods
+        },
+        allowed_headers: if args.cors_headers.is_empty() {
+            defaults.allowed_headers
+        } else {
+            args.cors_headers
+        },
+        tls,
+    };
+    let server =
+        McpServer::with_options(args.host.clone(), args.port, &project_dir, options).await?;
+
+    if let Some(fingerprint) = server.tls_fingerprint().await {
+        eprintln!("TLS certificate fingerprint (SHA-256): {}", fingerprint);
+    }
+
+    if args.read_only {
+        info!("Read-only mode: rejecting every operation that changes anything");
+    }
 
-    let server = McpServer::new(args.host, args.port, project_dir).await?;
-    server.run().await?;
+    match args.transport {
+        Transport::Http => {
+            info!(
+                "Starting Orbit MCP server at {}:{} for project at {:?}",
+                args.host, args.port, project_dir
+            );
+            server.run().await?;
+        }
+        Transport::Stdio => {
+            info!(
+                "Starting Orbit MCP server on stdio for project at {:?}",
+                project_dir
+            );
+            server.run_stdio().await?;
+        }
+    }
 
     Ok(())
 }
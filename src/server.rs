@@ -1,24 +1,164 @@
 // MCP server implementation
 
+use std::collections::{HashMap, HashSet, VecDeque};
 use std::net::SocketAddr;
 use std::path::{Path, PathBuf};
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use anyhow::{Context, Result};
 use axum::{
-    extract::ws::{Message, WebSocket, WebSocketUpgrade},
+    extract::{
+        ws::{close_code, CloseFrame, Message, WebSocket, WebSocketUpgrade},
+        Query,
+    },
+    middleware,
     response::IntoResponse,
     routing::{get, post},
     Extension, Router,
 };
+use axum_server::tls_rustls::RustlsConfig;
 use futures_util::{SinkExt, StreamExt};
-use log::{debug, error, info};
+use log::{error, info, warn};
 use serde::{Deserialize, Serialize};
 use serde_json::{json, Value};
-use tokio::sync::{broadcast, RwLock};
-use tower_http::{cors::CorsLayer, trace::TraceLayer};
+use tokio::sync::{mpsc, oneshot, RwLock};
+use tokio_util::sync::CancellationToken;
+use tower_http::trace::TraceLayer;
 
+use crate::api::auth::Scopes;
+use crate::api::build::BuildResult;
+use crate::api::jsonrpc::{JsonRpcNotification, JsonRpcRequest, JsonRpcResponse, JSONRPC_VERSION};
+use crate::api::logging::LoggingLevel;
+use crate::api::projects::ProjectRoot;
+use crate::api::testing::TestResult;
 use crate::handlers;
+use crate::handlers::rpc::RpcContext;
+use crate::transport;
+use crate::transport::streamable_http::{self, HttpSessions};
+use crate::utils::auth::{self, Granted};
+use crate::utils::error::McpError;
+use crate::utils::event_bus::{
+    EventBus, EventCursor, EventDelivery, EVENT_CHANNEL_CAPACITY, EVENT_HISTORY_SIZE,
+};
+use crate::utils::event_filter::EventFilter;
+use crate::utils::file_utils::{self, DenyList};
+use crate::utils::file_watcher::FileWatcher;
+use crate::utils::logging;
+use crate::utils::origin;
+use crate::utils::tls::TlsConfig;
+
+/// Default time a disconnected WebSocket session can be resumed, in seconds
+pub const DEFAULT_SESSION_GRACE_SECS: u64 = 60;
+
+/// Default time between keepalive pings, in seconds
+pub const DEFAULT_PING_INTERVAL_SECS: u64 = 30;
+
+/// Default time a client has to answer a ping, in seconds
+pub const DEFAULT_PONG_TIMEOUT_SECS: u64 = 10;
+
+/// Default time a client has to answer a request from the server, in seconds
+pub const DEFAULT_CLIENT_REQUEST_TIMEOUT_SECS: u64 = 120;
+
+/// Default time a Streamable HTTP session may go unused before it's closed,
+/// in seconds
+pub const DEFAULT_HTTP_SESSION_TIMEOUT_SECS: u64 = 1800;
+
+/// Default number of Streamable HTTP sessions that may be open at once
+pub const DEFAULT_MAX_HTTP_SESSIONS: usize = 256;
+
+/// Number of disconnected clients kept for the status endpoint
+const RECENT_CLIENTS: usize = 32;
+
+/// Settings for an MCP server
+///
+/// A zero duration turns the corresponding feature off.
+#[derive(Debug, Clone)]
+pub struct ServerOptions {
+    /// How long a disconnected WebSocket session can be resumed
+    pub session_grace: Duration,
+    /// How often WebSocket clients are pinged
+    pub ping_interval: Duration,
+    /// How long a WebSocket client has to answer a ping before it's dropped
+    pub pong_timeout: Duration,
+    /// How long a WebSocket client may send nothing before it's dropped
+    pub idle_timeout: Duration,
+    /// How long a client has to answer a request from the server, such as
+    /// `sampling/createMessage`
+    pub client_request_timeout: Duration,
+    /// How long a Streamable HTTP session may go without requests or an open
+    /// GET stream before it's closed
+    pub http_session_timeout: Duration,
+    /// Most Streamable HTTP sessions open at once, any number if zero
+    pub max_http_sessions: usize,
+    /// Token clients must present as a bearer token, granting every scope;
+    /// without any token, anyone who can reach the listener is let in
+    pub auth_token: Option<String>,
+    /// Further tokens, each granting only its scopes
+    pub scoped_tokens: HashMap<String, Scopes>,
+    /// Scopes clients that identify themselves by name are narrowed to, within
+    /// what their token grants; the name is whatever the client declares, so
+    /// this keeps well-behaved clients in check but is no access control
+    pub client_scopes: HashMap<String, Scopes>,
+    /// Reject every operation that changes the project or the server
+    pub read_only: bool,
+    /// Paths in the project clients can't read or write, as glob patterns
+    pub denied_paths: Vec<String>,
+    /// Directories, besides the project, client roots may lie in
+    pub allowed_roots: Vec<PathBuf>,
+    /// Origins browser pages may call the server from, such as
+    /// `http://localhost:5173`; none by default, `*` for any
+    pub allowed_origins: Vec<String>,
+    /// Host names clients may address the server by, against DNS rebinding;
+    /// the address the server binds to is always allowed, `*` allows any
+    pub allowed_hosts: Vec<String>,
+    /// HTTP methods browsers may use from an allowed origin
+    pub allowed_methods: Vec<String>,
+    /// Request headers browsers may send from an allowed origin
+    pub allowed_headers: Vec<String>,
+    /// Certificate to serve HTTPS and WSS with; plain HTTP without one
+    pub tls: Option<TlsConfig>,
+}
+
+impl ServerOptions {
+    /// Whether HTTP and WebSocket clients must present a token
+    pub fn requires_token(&self) -> bool {
+        self.auth_token.is_some() || !self.scoped_tokens.is_empty()
+    }
+}
+
+impl Default for ServerOptions {
+    fn default() -> Self {
+        Self {
+            session_grace: Duration::from_secs(DEFAULT_SESSION_GRACE_SECS),
+            ping_interval: Duration::from_secs(DEFAULT_PING_INTERVAL_SECS),
+            pong_timeout: Duration::from_secs(DEFAULT_PONG_TIMEOUT_SECS),
+            idle_timeout: Duration::ZERO,
+            client_request_timeout: Duration::from_secs(DEFAULT_CLIENT_REQUEST_TIMEOUT_SECS),
+            http_session_timeout: Duration::from_secs(DEFAULT_HTTP_SESSION_TIMEOUT_SECS),
+            max_http_sessions: DEFAULT_MAX_HTTP_SESSIONS,
+            auth_token: None,
+            scoped_tokens: HashMap::new(),
+            client_scopes: HashMap::new(),
+            read_only: false,
+            denied_paths: file_utils::DEFAULT_DENIED_PATHS
+                .iter()
+                .map(|pattern| pattern.to_string())
+                .collect(),
+            allowed_roots: Vec::new(),
+            allowed_origins: Vec::new(),
+            allowed_hosts: to_strings(origin::DEFAULT_ALLOWED_HOSTS),
+            allowed_methods: to_strings(origin::DEFAULT_ALLOWED_METHODS),
+            allowed_headers: to_strings(origin::DEFAULT_ALLOWED_HEADERS),
+            tls: None,
+        }
+    }
+}
+
+// Owned copies of a list of defaults
+fn to_strings(values: &[&str]) -> Vec<String> {
+    values.iter().map(|value| value.to_string()).collect()
+}
 
 /// Model Context Protocol server for the Orbit Framework
 pub struct McpServer {
@@ -31,7 +171,7 @@ pub struct McpServer {
     /// Shared server state
     state: Arc<RwLock<ServerState>>,
     /// Broadcast channel for events
-    event_tx: broadcast::Sender<ServerEvent>,
+    event_tx: EventBus,
 }
 
 /// Shared server state
@@ -40,6 +180,57 @@ pub struct ServerState {
     active_connections: usize,
     /// Connected clients
     clients: Vec<ClientInfo>,
+    /// Most recently removed clients, oldest first
+    recent_clients: VecDeque<ClientInfo>,
+    /// Result of the most recent build
+    last_build: Option<BuildResult>,
+    /// Result of the most recent test run
+    last_test: Option<TestResult>,
+    /// Channels for messages addressed to individual clients
+    outbound: HashMap<String, mpsc::UnboundedSender<Value>>,
+    /// Resource subscriptions, keyed by project directory and resource URI,
+    /// since the same URI names different files in different roots
+    resource_subscriptions: HashMap<(PathBuf, String), ResourceSubscription>,
+    /// Watcher for subscribed resource files
+    file_watcher: Option<FileWatcher>,
+    /// Cancellation tokens of in-flight requests, by client and request ID
+    in_flight: HashMap<String, HashMap<String, CancellationToken>>,
+    /// Event subscriptions, by client and subscription ID
+    event_subscriptions: HashMap<String, HashMap<String, EventFilter>>,
+    /// Client IDs, by the token that resumes their session
+    resume_tokens: HashMap<String, String>,
+    /// Sessions whose connection dropped, by client ID
+    detached_sessions: HashMap<String, DetachedSession>,
+    /// Requests sent to clients that wait for a response, by client and request ID
+    client_requests: HashMap<String, HashMap<String, oneshot::Sender<JsonRpcResponse>>>,
+    /// Number of requests sent to clients so far, used for their IDs
+    client_request_count: u64,
+    /// Server settings
+    options: ServerOptions,
+    /// SHA-256 fingerprint of the TLS certificate, if serving over TLS
+    tls_fingerprint: Option<String>,
+    /// Paths in the project clients can't read or write
+    deny_list: DenyList,
+    /// The project the server was started for
+    project_dir: PathBuf,
+}
+
+// What a client's connection leaves behind until the client resumes it
+struct DetachedSession {
+    /// Replies and notifications queued while the client was away
+    outbound_rx: mpsc::UnboundedReceiver<Value>,
+    /// The client's position in the event sequence
+    events: EventCursor,
+    /// When the session is dropped if nobody resumes it
+    expires_at: Instant,
+}
+
+// Clients subscribed to a single resource
+struct ResourceSubscription {
+    /// File backing the resource
+    path: PathBuf,
+    /// IDs of the subscribed clients
+    clients: HashSet<String>,
 }
 
 impl ServerState {
@@ -52,6 +243,445 @@ impl ServerState {
     pub fn clients(&self) -> &Vec<ClientInfo> {
         &self.clients
     }
+
+    /// Get the most recently disconnected clients, oldest first
+    pub fn recent_clients(&self) -> &VecDeque<ClientInfo> {
+        &self.recent_clients
+    }
+
+    /// Get the server settings
+    pub fn options(&self) -> &ServerOptions {
+        &self.options
+    }
+
+    /// Get the project the server was started for
+    pub fn project_dir(&self) -> &Path {
+        &self.project_dir
+    }
+
+    /// Get the paths in the project clients can't read or write
+    pub fn deny_list(&self) -> &DenyList {
+        &self.deny_list
+    }
+
+    /// Get the fingerprint of the TLS certificate, if serving over TLS
+    pub fn tls_fingerprint(&self) -> Option<&str> {
+        self.tls_fingerprint.as_deref()
+    }
+
+    /// Get the scopes a connected client has, none if it isn't connected
+    pub fn client_scopes(&self, client_id: &str) -> Scopes {
+        let scopes = self
+            .client(client_id)
+            .map(|client| client.scopes.clone())
+            .unwrap_or_default();
+        if self.options.read_only {
+            scopes.read_only()
+        } else {
+            scopes
+        }
+    }
+
+    /// Get a connected client by ID
+    pub fn client(&self, client_id: &str) -> Option<&ClientInfo> {
+        self.clients.iter().find(|client| client.id == client_id)
+    }
+
+    /// Get a mutable reference to a connected client by ID
+    pub fn client_mut(&mut self, client_id: &str) -> Option<&mut ClientInfo> {
+        self.clients
+            .iter_mut()
+            .find(|client| client.id == client_id)
+    }
+
+    /// Get the result of the most recent build
+    pub fn last_build(&self) -> Option<&BuildResult> {
+        self.last_build.as_ref()
+    }
+
+    /// Record the result of a build
+    pub fn set_last_build(&mut self, result: BuildResult) {
+        self.last_build = Some(result);
+    }
+
+    /// Get the result of the most recent test run
+    pub fn last_test(&self) -> Option<&TestResult> {
+        self.last_test.as_ref()
+    }
+
+    /// Record the result of a test run
+    pub fn set_last_test(&mut self, result: TestResult) {
+        self.last_test = Some(result);
+    }
+
+    /// Register a newly connected client and the channel used to reach it
+    pub fn add_client(&mut self, client: ClientInfo, outbound: mpsc::UnboundedSender<Value>) {
+        self.active_connections += 1;
+        self.outbound.insert(client.id.clone(), outbound);
+        self.clients.push(client);
+    }
+
+    /// Remove a disconnected client
+    pub fn remove_client(&mut self, client_id: &str) {
+        let Some(index) = self.clients.iter().position(|c| c.id == client_id) else {
+            return;
+        };
+
+        // Detached clients were already taken off the connection count
+        if self.detached_sessions.remove(client_id).is_none() {
+            self.active_connections -= 1;
+        }
+        let client = self.clients.remove(index);
+        if self.recent_clients.len() == RECENT_CLIENTS {
+            self.recent_clients.pop_front();
+        }
+        self.recent_clients.push_back(client);
+        self.outbound.remove(client_id);
+        self.event_subscriptions.remove(client_id);
+        self.resume_tokens.retain(|_, id| id != client_id);
+        // Dropping the senders fails requests still waiting for this client
+        self.client_requests.remove(client_id);
+
+        let subscribed: Vec<(PathBuf, String)> = self
+            .resource_subscriptions
+            .iter()
+            .filter(|(_, subscription)| subscription.clients.contains(client_id))
+            .map(|(key, _)| key.clone())
+            .collect();
+        for (project_dir, uri) in subscribed {
+            self.unsubscribe_resource(client_id, &project_dir, &uri);
+        }
+    }
+
+    /// Issue the token a client can use to resume its session after a disconnect
+    pub fn issue_resume_token(&mut self, client_id: &str) -> String {
+        let token = uuid::Uuid::new_v4().simple().to_string();
+        self.resume_tokens
+            .insert(token.clone(), client_id.to_string());
+        token
+    }
+
+    /// Note in a client's history that its connection ended
+    pub fn record_disconnect(&mut self, client_id: &str, reason: DisconnectReason) {
+        if let Some(client) = self.client_mut(client_id) {
+            client.record(ConnectionEvent::Disconnected, Some(reason));
+        }
+    }
+
+    // Keep a disconnected client's session around for the grace period
+    fn detach_client(
+        &mut self,
+        client_id: &str,
+        outbound_rx: mpsc::UnboundedReceiver<Value>,
+        events: EventCursor,
+    ) {
+        if self.client(client_id).is_none() {
+            return;
+        }
+
+        self.active_connections -= 1;
+        self.detached_sessions.insert(
+            client_id.to_string(),
+            DetachedSession {
+                outbound_rx,
+                events,
+                expires_at: Instant::now() + self.options.session_grace,
+            },
+        );
+    }
+
+    // Reattach a detached session, returning its client ID and what it left behind
+    fn resume_client(&mut self, resume_token: &str) -> Option<(String, DetachedSession)> {
+        let client_id = self.resume_tokens.get(resume_token)?.clone();
+        let session = self.detached_sessions.remove(&client_id)?;
+
+        self.active_connections += 1;
+        if let Some(client) = self.client_mut(&client_id) {
+            client.record(ConnectionEvent::Resumed, None);
+        }
+
+        Some((client_id, session))
+    }
+
+    // Drop a detached session once its grace period is over
+    fn expire_session(&mut self, client_id: &str) {
+        let expired = self
+            .detached_sessions
+            .get(client_id)
+            .is_some_and(|session| session.expires_at <= Instant::now());
+
+        if expired {
+            if let Some(client) = self.client_mut(client_id) {
+                client.record(ConnectionEvent::Expired, None);
+            }
+            self.cancel_client_requests(client_id);
+            self.remove_client(client_id);
+            info!("Session of client {} expired", client_id);
+        }
+    }
+
+    /// Track a request while it runs, returning the token that cancels it
+    pub fn start_request(&mut self, client_id: &str, request_id: &Value) -> CancellationToken {
+        let token = CancellationToken::new();
+        self.in_flight
+            .entry(client_id.to_string())
+            .or_default()
+            .insert(request_id.to_string(), token.clone());
+        token
+    }
+
+    /// Stop tracking a finished request
+    pub fn finish_request(&mut self, client_id: &str, request_id: &Value) {
+        if let Some(requests) = self.in_flight.get_mut(client_id) {
+            requests.remove(&request_id.to_string());
+            if requests.is_empty() {
+                self.in_flight.remove(client_id);
+            }
+        }
+    }
+
+    /// Cancel an in-flight request, returning whether it was found
+    pub fn cancel_request(&mut self, client_id: &str, request_id: &Value) -> bool {
+        let token = self
+            .in_flight
+            .get_mut(client_id)
+            .and_then(|requests| requests.remove(&request_id.to_string()));
+
+        match token {
+            Some(token) => {
+                token.cancel();
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Cancel every in-flight request of a client
+    pub fn cancel_client_requests(&mut self, client_id: &str) {
+        for (_, token) in self.in_flight.remove(client_id).unwrap_or_default() {
+            token.cancel();
+        }
+    }
+
+    /// Send a request to a client, returning its ID and where its response arrives
+    ///
+    /// Fails if the client didn't declare the capability the method needs.
+    pub fn send_request(
+        &mut self,
+        client_id: &str,
+        method: &str,
+        params: Value,
+    ) -> Result<(Value, oneshot::Receiver<JsonRpcResponse>), McpError> {
+        let client = self
+            .client(client_id)
+            .ok_or_else(|| McpError::ClientError(format!("Unknown client: {}", client_id)))?;
+        if !client.accepts_request(method) {
+            return Err(McpError::ClientError(format!(
+                "Client does not support {}",
+                method
+            )));
+        }
+
+        self.client_request_count += 1;
+        let id = json!(format!("server-{}", self.client_request_count));
+        let request = JsonRpcRequest {
+            jsonrpc: JSONRPC_VERSION.to_string(),
+            id: Some(id.clone()),
+            method: method.to_string(),
+            params: Some(params),
+        };
+
+        let sent = self
+            .outbound
+            .get(client_id)
+            .is_some_and(|outbound| outbound.send(json!(request)).is_ok());
+        if !sent {
+            return Err(McpError::ClientError("Client disconnected".to_string()));
+        }
+
+        let (response_tx, response_rx) = oneshot::channel();
+        self.client_requests
+            .entry(client_id.to_string())
+            .or_default()
+            .insert(id.to_string(), response_tx);
+        Ok((id, response_rx))
+    }
+
+    /// Pass a client's response to the request waiting for it, returning
+    /// whether there was one
+    pub fn complete_request(&mut self, client_id: &str, response: JsonRpcResponse) -> bool {
+        let waiting = self
+            .client_requests
+            .get_mut(client_id)
+            .and_then(|requests| requests.remove(&response.id.to_string()));
+        self.client_requests
+            .retain(|_, requests| !requests.is_empty());
+
+        match waiting {
+            Some(waiting) => waiting.send(response).is_ok(),
+            None => false,
+        }
+    }
+
+    /// Stop waiting for the response to a request sent to a client
+    pub fn abandon_request(&mut self, client_id: &str, request_id: &Value) {
+        if let Some(requests) = self.client_requests.get_mut(client_id) {
+            requests.remove(&request_id.to_string());
+            if requests.is_empty() {
+                self.client_requests.remove(client_id);
+            }
+        }
+    }
+
+    /// Add an event subscription for a client, returning its ID
+    pub fn subscribe_events(&mut self, client_id: &str, filter: EventFilter) -> String {
+        let subscription_id = uuid::Uuid::new_v4().to_string();
+        self.event_subscriptions
+            .entry(client_id.to_string())
+            .or_default()
+            .insert(subscription_id.clone(), filter);
+        subscription_id
+    }
+
+    /// Remove one of a client's event subscriptions, returning whether it existed
+    pub fn unsubscribe_events(&mut self, client_id: &str, subscription_id: &str) -> bool {
+        self.event_subscriptions
+            .get_mut(client_id)
+            .is_some_and(|subscriptions| subscriptions.remove(subscription_id).is_some())
+    }
+
+    /// Remove all of a client's event subscriptions, so it receives no events
+    pub fn unsubscribe_all_events(&mut self, client_id: &str) {
+        self.event_subscriptions
+            .insert(client_id.to_string(), HashMap::new());
+    }
+
+    /// Whether an event should be sent to a client
+    ///
+    /// Once a client subscribes, it only receives events matching one of its
+    /// subscriptions. Until then it receives every event, unless it identified
+    /// itself without declaring the `events` capability.
+    pub fn wants_event(&self, client_id: &str, event: &ServerEvent) -> bool {
+        match self.event_subscriptions.get(client_id) {
+            Some(subscriptions) => subscriptions.values().any(|filter| filter.matches(event)),
+            None => self
+                .client(client_id)
+                .is_none_or(|client| client.accepts_notification("server.event")),
+        }
+    }
+
+    /// Whether an event or drop notice should be sent to a client
+    pub fn wants_delivery(&self, client_id: &str, delivery: &EventDelivery) -> bool {
+        match delivery {
+            EventDelivery::Event(event) => self.wants_event(client_id, event),
+            EventDelivery::Dropped { .. } => {
+                self.event_subscriptions.contains_key(client_id)
+                    || self
+                        .client(client_id)
+                        .is_none_or(|client| client.accepts_notification("server.event"))
+            }
+        }
+    }
+
+    /// Send a message to a single client, returning whether it was queued
+    ///
+    /// Notifications the client can't handle are dropped.
+    pub fn send_to_client(&self, client_id: &str, message: Value) -> bool {
+        if let (Some(client), Some(method)) = (self.client(client_id), message["method"].as_str()) {
+            if !client.accepts_notification(method) {
+                return false;
+            }
+        }
+
+        self.outbound
+            .get(client_id)
+            .is_some_and(|outbound| outbound.send(message).is_ok())
+    }
+
+    /// Subscribe a client to changes of the file behind a resource of a project
+    pub fn subscribe_resource(
+        &mut self,
+        client_id: &str,
+        project_dir: &Path,
+        uri: &str,
+        path: &Path,
+    ) -> Result<()> {
+        let key = (project_dir.to_path_buf(), uri.to_string());
+        if !self.resource_subscriptions.contains_key(&key) {
+            if !self.is_watched(path) {
+                if let Some(watcher) = self.file_watcher.as_mut() {
+                    watcher.watch(path)?;
+                }
+            }
+            self.resource_subscriptions.insert(
+                key.clone(),
+                ResourceSubscription {
+                    path: path.to_path_buf(),
+                    clients: HashSet::new(),
+                },
+            );
+        }
+
+        if let Some(subscription) = self.resource_subscriptions.get_mut(&key) {
+            subscription.clients.insert(client_id.to_string());
+        }
+
+        Ok(())
+    }
+
+    /// Unsubscribe a client from a resource of a project
+    pub fn unsubscribe_resource(&mut self, client_id: &str, project_dir: &Path, uri: &str) {
+        let key = (project_dir.to_path_buf(), uri.to_string());
+        let Some(subscription) = self.resource_subscriptions.get_mut(&key) else {
+            return;
+        };
+
+        subscription.clients.remove(client_id);
+
+        if subscription.clients.is_empty() {
+            if let Some(subscription) = self.resource_subscriptions.remove(&key) {
+                // Other projects may still watch the same file
+                if !self.is_watched(&subscription.path) {
+                    if let Some(watcher) = self.file_watcher.as_mut() {
+                        watcher.unwatch(&subscription.path);
+                    }
+                }
+            }
+        }
+    }
+
+    // Whether a subscription is watching a file
+    fn is_watched(&self, path: &Path) -> bool {
+        self.resource_subscriptions
+            .values()
+            .any(|subscription| subscription.path == path)
+    }
+
+    /// Get the projects and URIs of the subscribed resources, and their
+    /// clients, for a changed file
+    pub fn resource_subscribers(&self, path: &Path) -> Vec<(&Path, &str, Vec<&str>)> {
+        self.resource_subscriptions
+            .iter()
+            .filter(|(_, subscription)| subscription.path == path)
+            .map(|((project_dir, uri), subscription)| {
+                (
+                    project_dir.as_path(),
+                    uri.as_str(),
+                    subscription.clients.iter().map(String::as_str).collect(),
+                )
+            })
+            .collect()
+    }
+}
+
+// Query parameters of the WebSocket endpoint
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WebSocketParams {
+    /// ID of the last event the client saw before reconnecting
+    last_event_id: Option<u64>,
+    /// Token from the welcome message of the session to resume
+    resume_token: Option<String>,
 }
 
 /// Information about a connected client
@@ -61,15 +691,165 @@ pub struct ClientInfo {
     pub id: String,
     /// Client name
     pub name: Option<String>,
-    /// Client capabilities
+    /// Client version
+    pub version: Option<String>,
+    /// Protocol version agreed with the client
+    pub protocol_version: Option<String>,
+    /// Client capabilities, such as `sampling` or `roots.listChanged`
     pub capabilities: Vec<String>,
     /// Connection time
     pub connected_at: chrono::DateTime<chrono::Utc>,
+    /// When the connection dropped, while the session waits to be resumed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub disconnected_at: Option<chrono::DateTime<chrono::Utc>>,
+    /// Disconnects and resumptions of the client's session, oldest first
+    #[serde(default)]
+    pub history: Vec<ConnectionRecord>,
+    /// Minimum level of the log messages forwarded to the client, if it asked for any
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<LoggingLevel>,
+    /// Projects the client has open, from its `roots/list`
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub roots: Vec<ProjectRoot>,
+    /// What the client is allowed to do
+    #[serde(default)]
+    pub scopes: Scopes,
+}
+
+/// An entry in a client's connection history
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionRecord {
+    /// What happened
+    pub event: ConnectionEvent,
+    /// Why the connection ended, for disconnects
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<DisconnectReason>,
+    /// When it happened
+    pub at: chrono::DateTime<chrono::Utc>,
+}
+
+/// Something that happened to a client's session
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionEvent {
+    /// The connection ended
+    Disconnected,
+    /// The client reconnected and resumed its session
+    Resumed,
+    /// Nobody resumed the session within the grace period
+    Expired,
+}
+
+/// Why a client's connection ended
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DisconnectReason {
+    /// The client closed the connection
+    ClientClosed,
+    /// The connection failed or was reset
+    ConnectionLost,
+    /// A message couldn't be sent to the client
+    SendFailed,
+    /// The client didn't answer a ping in time
+    PongTimeout,
+    /// The client sent nothing for too long
+    IdleTimeout,
+}
+
+impl std::fmt::Display for DisconnectReason {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let reason = match self {
+            DisconnectReason::ClientClosed => "client closed the connection",
+            DisconnectReason::ConnectionLost => "connection lost",
+            DisconnectReason::SendFailed => "failed to send to client",
+            DisconnectReason::PongTimeout => "no pong received in time",
+            DisconnectReason::IdleTimeout => "idle for too long",
+        };
+        f.write_str(reason)
+    }
+}
+
+impl ClientInfo {
+    /// Create an anonymous client that connected just now
+    pub fn new(id: String) -> Self {
+        Self {
+            id,
+            name: None,
+            version: None,
+            protocol_version: None,
+            capabilities: Vec::new(),
+            connected_at: chrono::Utc::now(),
+            disconnected_at: None,
+            history: Vec::new(),
+            log_level: None,
+            roots: Vec::new(),
+            scopes: Scopes::all(),
+        }
+    }
+
+    /// Limit the client to the scopes granted to its connection
+    pub fn with_scopes(mut self, scopes: Scopes) -> Self {
+        self.scopes = scopes;
+        self
+    }
+
+    // Add an entry to the connection history
+    fn record(&mut self, event: ConnectionEvent, reason: Option<DisconnectReason>) {
+        let at = chrono::Utc::now();
+        self.disconnected_at = match event {
+            ConnectionEvent::Resumed => None,
+            _ => self.disconnected_at.or(Some(at)),
+        };
+        self.history.push(ConnectionRecord { event, reason, at });
+    }
+
+    /// Whether the client has introduced itself with `initialize` or `client.hello`
+    pub fn is_identified(&self) -> bool {
+        self.name.is_some()
+    }
+
+    /// Whether the client declared a capability
+    ///
+    /// Custom capabilities may be declared at the top level or, as MCP
+    /// clients do, under `experimental`.
+    pub fn supports(&self, capability: &str) -> bool {
+        self.capabilities.iter().any(|declared| {
+            declared == capability || declared.strip_prefix("experimental.") == Some(capability)
+        })
+    }
+
+    /// Whether the server may send the client a notification
+    ///
+    /// Clients that never identified themselves get everything, as before.
+    pub fn accepts_notification(&self, method: &str) -> bool {
+        if !self.is_identified() {
+            return true;
+        }
+
+        match method {
+            "server.event" => self.supports("events"),
+            "notifications/roots/list_changed" => self.supports("roots.listChanged"),
+            _ => true,
+        }
+    }
+
+    /// Whether the server may send the client a request
+    pub fn accepts_request(&self, method: &str) -> bool {
+        match method {
+            "sampling/createMessage" => self.supports("sampling"),
+            "roots/list" => self.supports("roots"),
+            "elicitation/create" => self.supports("elicitation"),
+            _ => true,
+        }
+    }
 }
 
 /// Server event for broadcasting
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ServerEvent {
+    /// Sequence ID, assigned when the event is sent
+    #[serde(default)]
+    pub id: u64,
     /// Event type
     pub event_type: String,
     /// Event payload
@@ -78,15 +858,113 @@ pub struct ServerEvent {
     pub timestamp: chrono::DateTime<chrono::Utc>,
 }
 
+impl ServerEvent {
+    /// Create an event stamped with the current time
+    pub fn new(event_type: impl Into<String>, payload: Value) -> Self {
+        Self {
+            id: 0,
+            event_type: event_type.into(),
+            payload,
+            timestamp: chrono::Utc::now(),
+        }
+    }
+
+    /// Wrap the event in the `server.event` notification sent to clients
+    pub fn to_notification(&self) -> JsonRpcNotification {
+        JsonRpcNotification::new(
+            "server.event",
+            json!({
+                "id": self.id,
+                "type": self.event_type,
+                "payload": self.payload,
+                "timestamp": self.timestamp
+            }),
+        )
+    }
+
+    /// Read an event back from a `server.event` notification
+    pub fn from_notification(notification: &JsonRpcNotification) -> Option<Self> {
+        if notification.method != "server.event" {
+            return None;
+        }
+
+        let params = notification.params.as_ref()?;
+        Some(Self {
+            id: params["id"].as_u64().unwrap_or_default(),
+            event_type: params["type"].as_str()?.to_string(),
+            payload: params["payload"].clone(),
+            timestamp: serde_json::from_value(params["timestamp"].clone()).ok()?,
+        })
+    }
+}
+
 impl McpServer {
-    /// Create a new MCP server
+    /// Create a new MCP server with default options
     pub async fn new<P: AsRef<Path>>(host: String, port: u16, project_dir: P) -> Result<Self> {
-        let (event_tx, _) = broadcast::channel(100);
+        Self::with_options(host, port, project_dir, ServerOptions::default()).await
+    }
+
+    /// Create a new MCP server with the given options
+    pub async fn with_options<P: AsRef<Path>>(
+        host: String,
+        port: u16,
+        project_dir: P,
+        options: ServerOptions,
+    ) -> Result<Self> {
+        let event_tx = EventBus::new(EVENT_CHANNEL_CAPACITY, EVENT_HISTORY_SIZE);
+        let deny_list = DenyList::new(&options.denied_paths)?.within(
+            std::iter::once(project_dir.as_ref())
+                .chain(options.allowed_roots.iter().map(PathBuf::as_path)),
+        );
+        let tls_fingerprint = options
+            .tls
+            .as_ref()
+            .map(TlsConfig::fingerprint)
+            .transpose()?;
+
+        // Resource subscriptions still work without a watcher, they just never fire
+        let (file_watcher, file_changes) = match FileWatcher::new() {
+            Ok((watcher, changes)) => (Some(watcher), Some(changes)),
+            Err(e) => {
+                warn!("File watching unavailable: {:#}", e);
+                (None, None)
+            }
+        };
+
         let state = Arc::new(RwLock::new(ServerState {
             active_connections: 0,
             clients: Vec::new(),
+            recent_clients: VecDeque::new(),
+            last_build: None,
+            last_test: None,
+            outbound: HashMap::new(),
+            resource_subscriptions: HashMap::new(),
+            file_watcher,
+            in_flight: HashMap::new(),
+            event_subscriptions: HashMap::new(),
+            resume_tokens: HashMap::new(),
+            detached_sessions: HashMap::new(),
+            client_requests: HashMap::new(),
+            client_request_count: 0,
+            options,
+            tls_fingerprint,
+            deny_list,
+            project_dir: project_dir.as_ref().to_path_buf(),
         }));
 
+        tokio::spawn(handlers::logging::forward_log_records(
+            state.clone(),
+            logging::subscribe(),
+        ));
+
+        if let Some(file_changes) = file_changes {
+            tokio::spawn(handlers::resources::forward_file_changes(
+                state.clone(),
+                event_tx.clone(),
+                file_changes,
+            ));
+        }
+
         Ok(Self {
             host,
             port,
@@ -96,20 +974,69 @@ impl McpServer {
         })
     }
 
+    /// SHA-256 fingerprint of the TLS certificate, if serving over TLS
+    pub async fn tls_fingerprint(&self) -> Option<String> {
+        self.state
+            .read()
+            .await
+            .tls_fingerprint()
+            .map(str::to_string)
+    }
+
     /// Run the MCP server
     pub async fn run(&self) -> Result<()> {
+        let addr = format!("{}:{}", self.host, self.port)
+            .parse::<SocketAddr>()
+            .context("Invalid address format")?;
+        let listener = std::net::TcpListener::bind(addr)
+            .with_context(|| format!("Failed to bind to {}", addr))?;
+
+        self.serve(listener).await
+    }
+
+    /// Run the MCP server on a listener that's already bound, such as one on
+    /// port 0 that the operating system picked a free port for
+    pub async fn serve(&self, listener: std::net::TcpListener) -> Result<()> {
+        let addr = listener.local_addr().context("Listener has no address")?;
         let state = self.state.clone();
         let event_tx = self.event_tx.clone();
         let project_dir = self.project_dir.clone();
+        let deny_list = self.state.read().await.deny_list().clone();
+        let mut options = self.state.read().await.options().clone();
+        // Clients can always address the server by the address it binds to
+        options.allowed_hosts.push(if self.host.contains(':') {
+            format!("[{}]", self.host)
+        } else {
+            self.host.clone()
+        });
+        if self.host == "0.0.0.0" || self.host == "::" {
+            warn!(
+                "Listening on all interfaces; clients must address the server by an allowed host ({})",
+                options.allowed_hosts.join(", ")
+            );
+        }
+        let cors = origin::cors_layer(&options)?;
+        let http_sessions = HttpSessions::new(options.max_http_sessions);
+        http_sessions.spawn_reaper(state.clone(), options.http_session_timeout);
+        let tls = options.tls.clone();
+        let options = Arc::new(options);
 
         // Create the API router
         let api_router = Router::new()
             // WebSocket handler
             .route("/ws", get(Self::websocket_handler))
+            // Streamable HTTP transport
+            .route(
+                "/mcp",
+                post(streamable_http::post_handler)
+                    .get(streamable_http::get_handler)
+                    .delete(streamable_http::delete_handler),
+            )
             // Status endpoint
             .route("/status", get(handlers::status_handler))
             // Documentation endpoint
             .route("/docs", get(handlers::docs_handler))
+            .route("/openapi.json", get(handlers::openapi_handler))
             // Component API endpoints
             .route(
                 "/components",
@@ -125,7 +1052,17 @@ impl McpServer {
             // Project API endpoints
             .route("/project", get(handlers::projects::get_project_info))
             .route("/project/build", post(handlers::build::build_project))
-            .route("/project/test", post(handlers::testing::run_tests));
+            .route("/project/test", post(handlers::testing::run_tests))
+            // Check the token on every endpoint, including the WebSocket upgrade
+            .layer(middleware::from_fn_with_state(
+                options.clone(),
+                auth::authorize,
+            ))
+            // Turn away other sites and rebound host names before anything else
+            .layer(middleware::from_fn_with_state(
+                options,
+                origin::check_origin,
+            ));
 
         // Create the main router
         let app = Router::new()
@@ -133,32 +1070,64 @@ impl McpServer {
             .layer(Extension(state))
             .layer(Extension(event_tx))
             .layer(Extension(project_dir))
+            .layer(Extension(deny_list))
+            .layer(Extension(http_sessions))
             .layer(TraceLayer::new_for_http())
-            .layer(CorsLayer::permissive());
+            .layer(cors);
 
         // Start the server
-        let addr = format!("{}:{}", self.host, self.port)
-            .parse::<SocketAddr>()
-            .context("Invalid address format")?;
+        match tls {
+            Some(tls) => {
+                let config = RustlsConfig::from_pem_file(&tls.cert_path, &tls.key_path)
+                    .await
+                    .with_context(|| {
+                        format!(
+                            "Failed to load TLS certificate {:?} and key {:?}",
+                            tls.cert_path, tls.key_path
+                        )
+                    })?;
 
-        info!("MCP server listening on {}", addr);
-        axum::Server::bind(&addr)
-            .serve(app.into_make_service())
-            .await
-            .context("Failed to start server")?;
+                info!("MCP server listening on {} (HTTPS and WSS)", addr);
+                axum_server::from_tcp_rustls(listener, config)
+                    .serve(app.into_make_service())
+                    .await
+                    .context("Failed to start server")?;
+            }
+            None => {
+                info!("MCP server listening on {}", addr);
+                axum::Server::from_tcp(listener)
+                    .context("Failed to start server")?
+                    .serve(app.into_make_service())
+                    .await
+                    .context("Failed to start server")?;
+            }
+        }
 
         Ok(())
     }
 
+    /// Serve a single client over stdin/stdout instead of binding a listener
+    pub async fn run_stdio(&self) -> Result<()> {
+        transport::stdio::serve(
+            self.state.clone(),
+            self.event_tx.clone(),
+            self.project_dir.clone(),
+        )
+        .await
+    }
+
     // WebSocket handler for real-time communication
     async fn websocket_handler(
         ws: WebSocketUpgrade,
+        Query(params): Query<WebSocketParams>,
         Extension(state): Extension<Arc<RwLock<ServerState>>>,
-        Extension(event_tx): Extension<broadcast::Sender<ServerEvent>>,
+        Extension(event_tx): Extension<EventBus>,
         Extension(project_dir): Extension<PathBuf>,
+        Extension(Granted(scopes)): Extension<Granted>,
     ) -> impl IntoResponse {
-        ws.on_upgrade(|socket| async {
-            Self::handle_websocket_connection(socket, state, event_tx, project_dir).await;
+        ws.on_upgrade(move |socket| async move {
+            Self::handle_websocket_connection(socket, state, event_tx, project_dir, params, scopes)
+                .await;
         })
     }
 
@@ -166,31 +1135,67 @@ impl McpServer {
     async fn handle_websocket_connection(
         socket: WebSocket,
         state: Arc<RwLock<ServerState>>,
-        event_tx: broadcast::Sender<ServerEvent>,
+        event_tx: EventBus,
         project_dir: PathBuf,
+        params: WebSocketParams,
+        scopes: Scopes,
     ) {
         // Split the socket
         let (mut sender, mut receiver) = socket.split();
 
-        // Generate a unique client ID
-        let client_id = uuid::Uuid::new_v4().to_string();
+        // Pick up a detached session, or start a new one
+        let resumed = match &params.resume_token {
+            Some(resume_token) => state.write().await.resume_client(resume_token),
+            None => None,
+        };
 
-        // Add the client to the state
-        {
-            let mut state = state.write().await;
-            state.active_connections += 1;
-            state.clients.push(ClientInfo {
-                id: client_id.clone(),
-                name: None,
-                capabilities: Vec::new(),
-                connected_at: chrono::Utc::now(),
-            });
-        }
+        let (client_id, resume_token, outbound_tx, mut outbound_rx, stored_events) = match resumed {
+            Some((client_id, session)) => {
+                let mut state = state.write().await;
+                let Some(outbound_tx) = state.outbound.get(&client_id).cloned() else {
+                    return;
+                };
+                // The session can't do more than the token resuming it allows
+                if let Some(client) = state.client_mut(&client_id) {
+                    client.scopes = client.scopes.intersection(&scopes);
+                }
 
-        info!("New WebSocket client connected: {}", client_id);
+                info!("WebSocket client resumed its session: {}", client_id);
+                (
+                    client_id,
+                    params.resume_token.clone().unwrap_or_default(),
+                    outbound_tx,
+                    session.outbound_rx,
+                    Some(session.events),
+                )
+            }
+            None => {
+                // Generate a unique client ID
+                let client_id = uuid::Uuid::new_v4().to_string();
 
-        // Subscribe to events
-        let mut event_rx = event_tx.subscribe();
+                // Replies and notifications addressed to this client only
+                let (outbound_tx, outbound_rx) = mpsc::unbounded_channel::<Value>();
+
+                // Add the client to the state
+                let mut state = state.write().await;
+                state.add_client(
+                    ClientInfo::new(client_id.clone()).with_scopes(scopes),
+                    outbound_tx.clone(),
+                );
+                let resume_token = state.issue_resume_token(&client_id);
+
+                info!("New WebSocket client connected: {}", client_id);
+                (client_id, resume_token, outbound_tx, outbound_rx, None)
+            }
+        };
+        let resumed = stored_events.is_some();
+
+        // Subscribe to events, replaying what a reconnecting client missed
+        let mut events = match (params.last_event_id, stored_events) {
+            (Some(last_event_id), _) => event_tx.subscribe_after(last_event_id),
+            (None, Some(events)) => events,
+            (None, None) => event_tx.subscribe(),
+        };
 
         // Send welcome message
         let welcome = json!({
@@ -199,6 +1204,11 @@ impl McpServer {
             "params": {
                 "serverId": "orbit-mcp-server",
                 "version": env!("CARGO_PKG_VERSION"),
+                "clientId": client_id,
+                "resumeToken": resume_token,
+                "resumed": resumed,
+                "sessionGraceSeconds": state.read().await.options().session_grace.as_secs(),
+                "lastEventId": event_tx.last_event_id(),
                 "capabilities": [
                     "components",
                     "projects",
@@ -214,107 +1224,172 @@ impl McpServer {
         }
 
         // Handle incoming messages (in a separate task)
-        let state_clone = state.clone();
-        let event_tx_clone = event_tx.clone();
-        let project_dir_clone = project_dir.clone();
-        let client_id_clone = client_id.clone();
+        let ctx = RpcContext {
+            client_id: client_id.clone(),
+            state: state.clone(),
+            event_tx: event_tx.clone(),
+            project_dir: project_dir.clone(),
+            outbound: outbound_tx,
+            progress_token: None,
+            granted: Scopes::all(),
+        };
 
-        let receive_task = tokio::spawn(async move {
-            while let Some(Ok(msg)) = receiver.next().await {
-                match msg {
-                    Message::Text(text) => {
-                        // Handle JSON-RPC request
-                        Self::handle_jsonrpc_message(
-                            text,
-                            &client_id_clone,
-                            &state_clone,
-                            &event_tx_clone,
-                            &project_dir_clone,
-                        )
-                        .await;
+        // Replies to keepalive pings, and any frame at all, show the client is
+        // still there; a client that sends nothing else is idle
+        let options = state.read().await.options().clone();
+        let after = |period: Duration| (!period.is_zero()).then(|| Instant::now() + period);
+        let (control_tx, mut control_rx) = mpsc::unbounded_channel::<Message>();
+
+        let mut receive_task = tokio::spawn(async move {
+            let mut next_ping = after(options.ping_interval);
+            let mut pong_deadline = None;
+            let mut idle_deadline = after(options.idle_timeout);
+
+            loop {
+                tokio::select! {
+                    msg = receiver.next() => {
+                        pong_deadline = None;
+
+                        match msg {
+                            Some(Ok(Message::Text(text))) => {
+                                idle_deadline = after(options.idle_timeout);
+
+                                // Handle each request in its own task so a slow build
+                                // doesn't hold up the rest of the connection
+                                let ctx = ctx.clone();
+                                tokio::spawn(async move {
+                                    if let Some(response) =
+                                        handlers::rpc::handle_message(&ctx, &text).await
+                                    {
+                                        let _ = ctx.outbound.send(json!(response));
+                                    }
+                                });
+                            }
+                            Some(Ok(Message::Close(_))) => return DisconnectReason::ClientClosed,
+                            Some(Ok(_)) => {} // Ignore other message types
+                            Some(Err(_)) | None => return DisconnectReason::ConnectionLost,
+                        }
+                    }
+                    _ = sleep_until(next_ping) => {
+                        let _ = control_tx.send(Message::Ping(Vec::new()));
+                        next_ping = after(options.ping_interval);
+                        if pong_deadline.is_none() {
+                            pong_deadline = after(options.pong_timeout);
+                        }
                     }
-                    Message::Close(_) => break,
-                    _ => {} // Ignore other message types
+                    _ = sleep_until(pong_deadline) => return DisconnectReason::PongTimeout,
+                    _ = sleep_until(idle_deadline) => return DisconnectReason::IdleTimeout,
                 }
             }
         });
 
-        // Forward broadcast events to the client
-        let state_clone = state.clone();
+        // Forward replies and subscribed events to the client
         let client_id_clone = client_id.clone();
+        let send_state = state.clone();
+        let stop = CancellationToken::new();
+        let stop_send = stop.clone();
+
+        let mut send_task = tokio::spawn(async move {
+            loop {
+                let message = tokio::select! {
+                    _ = stop_send.cancelled() => break,
+                    Some(frame) = control_rx.recv() => frame,
+                    Some(message) = outbound_rx.recv() => Message::Text(message.to_string()),
+                    delivery = events.next() => {
+                        let Some(delivery) = delivery else {
+                            break;
+                        };
+
+                        if !send_state.read().await.wants_delivery(&client_id_clone, &delivery) {
+                            continue;
+                        }
 
-        let send_task = tokio::spawn(async move {
-            while let Ok(event) = event_rx.recv().await {
-                // Serialize the event to JSON
-                let event_json = json!({
-                    "jsonrpc": "2.0",
-                    "method": "server.event",
-                    "params": {
-                        "type": event.event_type,
-                        "payload": event.payload,
-                        "timestamp": event.timestamp
+                        // Serialize the event to JSON
+                        Message::Text(json!(delivery.to_notification()).to_string())
                     }
-                });
+                };
 
-                // Send the event to the client
-                if let Err(e) = sender.send(Message::Text(event_json.to_string())).await {
-                    error!("Failed to send event to client {}: {}", client_id_clone, e);
+                // Send the message to the client
+                if let Err(e) = sender.send(message).await {
+                    error!(
+                        "Failed to send message to client {}: {}",
+                        client_id_clone, e
+                    );
                     break;
                 }
             }
+
+            // Hand the queues back so the session can be resumed
+            (sender, outbound_rx, events)
         });
 
-        // Wait for either task to finish
-        tokio::select! {
-            _ = receive_task => {},
-            _ = send_task => {},
-        }
+        // Wait for either task to finish, then stop the other
+        let (reason, queues) = tokio::select! {
+            reason = &mut receive_task => {
+                stop.cancel();
+                (
+                    reason.unwrap_or(DisconnectReason::ConnectionLost),
+                    send_task.await.ok(),
+                )
+            },
+            queues = &mut send_task => {
+                receive_task.abort();
+                (DisconnectReason::SendFailed, queues.ok())
+            },
+        };
 
-        // Client disconnected, update state
-        {
-            let mut state = state.write().await;
-            state.active_connections -= 1;
-            state.clients.retain(|c| c.id != client_id);
-        }
+        // Tell a client we give up on why, if it's still listening
+        let queues = match queues {
+            Some((mut sender, outbound_rx, events)) => {
+                if matches!(
+                    reason,
+                    DisconnectReason::PongTimeout | DisconnectReason::IdleTimeout
+                ) {
+                    let close = Message::Close(Some(CloseFrame {
+                        code: close_code::AWAY,
+                        reason: reason.to_string().into(),
+                    }));
+                    let _ = tokio::time::timeout(Duration::from_secs(1), sender.send(close)).await;
+                }
+                Some((outbound_rx, events))
+            }
+            None => None,
+        };
 
-        info!("WebSocket client disconnected: {}", client_id);
-    }
+        // Client disconnected, keep its session for a while if we can
+        let grace = options.session_grace;
+        let mut state_guard = state.write().await;
+        state_guard.record_disconnect(&client_id, reason);
 
-    // Handle JSON-RPC messages
-    async fn handle_jsonrpc_message(
-        text: String,
-        client_id: &str,
-        state: &Arc<RwLock<ServerState>>,
-        event_tx: &broadcast::Sender<ServerEvent>,
-        project_dir: &PathBuf,
-    ) {
-        // Parse the JSON-RPC message
-        let request: Result<Value, _> = serde_json::from_str(&text);
-
-        match request {
-            Ok(request) => {
-                debug!(
-                    "Received JSON-RPC request from client {}: {:?}",
-                    client_id, request
+        match queues {
+            Some((outbound_rx, events))
+                if !grace.is_zero() && reason != DisconnectReason::IdleTimeout =>
+            {
+                state_guard.detach_client(&client_id, outbound_rx, events);
+                drop(state_guard);
+                info!(
+                    "WebSocket client {} disconnected ({}), session kept for {}s",
+                    client_id,
+                    reason,
+                    grace.as_secs()
                 );
 
-                // Extract method and params
-                let method = request["method"].as_str();
-                let id = request["id"].as_u64();
-
-                if let Some(method) = method {
-                    // Handle different methods
-                    match method {
-                        // Add method handling here
-                        _ => {
-                            debug!("Unhandled method: {}", method);
-                        }
-                    }
-                }
+                tokio::time::sleep(grace).await;
+                state.write().await.expire_session(&client_id);
             }
-            Err(e) => {
-                error!("Failed to parse JSON-RPC message: {}", e);
+            _ => {
+                state_guard.cancel_client_requests(&client_id);
+                state_guard.remove_client(&client_id);
+                info!("WebSocket client {} disconnected ({})", client_id, reason);
             }
         }
     }
 }
+
+// Sleep until a deadline, or forever without one
+async fn sleep_until(deadline: Option<Instant>) {
+    match deadline {
+        Some(deadline) => tokio::time::sleep_until(deadline.into()).await,
+        None => std::future::pending().await,
+    }
+}
@@ -0,0 +1,4 @@
+// Transport module definition
+
+pub mod stdio;
+pub mod streamable_http;
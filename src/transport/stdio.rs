@@ -0,0 +1,135 @@
+// stdio transport: newline-delimited JSON-RPC over stdin/stdout
+
+use std::path::PathBuf;
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use log::{error, info};
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+use tokio::sync::{mpsc, RwLock};
+
+use crate::api::auth::Scopes;
+use crate::handlers;
+use crate::handlers::rpc::RpcContext;
+use crate::server::{ClientInfo, DisconnectReason, ServerState};
+use crate::utils::event_bus::{EventBus, EventCursor};
+
+/// Serve the process's parent over stdin/stdout until stdin is closed
+///
+/// Every line on stdin is one JSON-RPC message and every line written to
+/// stdout is one reply or notification, so nothing else may print to stdout
+/// while this runs.
+pub async fn serve(
+    state: Arc<RwLock<ServerState>>,
+    event_tx: EventBus,
+    project_dir: PathBuf,
+) -> Result<()> {
+    // The parent process is our only client
+    let client_id = uuid::Uuid::new_v4().to_string();
+
+    let (outbound_tx, mut outbound_rx) = mpsc::unbounded_channel::<Value>();
+
+    state
+        .write()
+        .await
+        .add_client(ClientInfo::new(client_id.clone()), outbound_tx.clone());
+
+    info!("Serving MCP over stdio as client {}", client_id);
+
+    let mut events = event_tx.subscribe();
+
+    let ctx = RpcContext {
+        client_id: client_id.clone(),
+        state: state.clone(),
+        event_tx,
+        project_dir,
+        outbound: outbound_tx,
+        progress_token: None,
+        granted: Scopes::all(),
+    };
+
+    // Read requests from stdin. Once stdin is closed the reader unregisters the
+    // client and drops its context, so the writer below stops as soon as every
+    // in-flight reply has been sent
+    let reader = tokio::spawn(async move {
+        let mut lines = BufReader::new(tokio::io::stdin()).lines();
+
+        loop {
+            let line = match lines.next_line().await {
+                Ok(Some(line)) => line,
+                Ok(None) => break,
+                Err(e) => {
+                    error!("Failed to read from stdin: {}", e);
+                    break;
+                }
+            };
+
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            let ctx = ctx.clone();
+            tokio::spawn(async move {
+                if let Some(response) = handlers::rpc::handle_message(&ctx, &line).await {
+                    let _ = ctx.outbound.send(json!(response));
+                }
+            });
+        }
+
+        let mut state = ctx.state.write().await;
+        state.record_disconnect(&ctx.client_id, DisconnectReason::ClientClosed);
+        state.remove_client(&ctx.client_id);
+    });
+
+    // Write replies and broadcast events to stdout, one message per line
+    let result = write_messages(&state, &client_id, &mut outbound_rx, &mut events).await;
+
+    reader.abort();
+    {
+        let mut state = state.write().await;
+        if result.is_err() {
+            state.record_disconnect(&client_id, DisconnectReason::SendFailed);
+        }
+        state.cancel_client_requests(&client_id);
+        state.remove_client(&client_id);
+    }
+
+    info!("stdio client disconnected: {}", client_id);
+
+    result
+}
+
+// Forward outbound messages to stdout until every sender is gone
+async fn write_messages(
+    state: &Arc<RwLock<ServerState>>,
+    client_id: &str,
+    outbound_rx: &mut mpsc::UnboundedReceiver<Value>,
+    events: &mut EventCursor,
+) -> Result<()> {
+    let mut stdout = tokio::io::stdout();
+
+    loop {
+        let message = tokio::select! {
+            message = outbound_rx.recv() => match message {
+                Some(message) => message,
+                None => return Ok(()),
+            },
+            delivery = events.next() => match delivery {
+                Some(delivery) if state.read().await.wants_delivery(client_id, &delivery) => {
+                    json!(delivery.to_notification())
+                }
+                Some(_) => continue,
+                None => return Ok(()),
+            },
+        };
+
+        let mut line = message.to_string();
+        line.push('\n');
+        stdout
+            .write_all(line.as_bytes())
+            .await
+            .context("Failed to write to stdout")?;
+        stdout.flush().await.context("Failed to flush stdout")?;
+    }
+}
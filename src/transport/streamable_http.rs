@@ -0,0 +1,505 @@
+// MCP Streamable HTTP transport: JSON-RPC over POST, notifications over SSE
+
+use std::collections::HashMap;
+use std::convert::Infallible;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use axum::{
+    extract::Extension,
+    http::{header, HeaderMap, HeaderValue, StatusCode},
+    response::{
+        sse::{Event, KeepAlive, Sse},
+        IntoResponse, Json, Response,
+    },
+};
+use futures_util::{stream, Stream};
+use log::{debug, info, warn};
+use serde_json::{json, Value};
+use tokio::sync::{mpsc, oneshot, RwLock};
+
+use crate::api::jsonrpc::{self, JsonRpcError, JsonRpcReply, JsonRpcResponse};
+use crate::handlers;
+use crate::handlers::rpc::RpcContext;
+use crate::server::{ClientInfo, DisconnectReason, ServerState};
+use crate::utils::auth::{Granted, TokenId};
+use crate::utils::error::McpError;
+use crate::utils::event_bus::{EventBus, EventCursor, EventDelivery};
+
+/// Header carrying the session ID assigned on `initialize`
+pub const SESSION_HEADER: &str = "mcp-session-id";
+
+/// Header a reconnecting SSE client sends with the last event ID it saw
+pub const LAST_EVENT_ID_HEADER: &str = "last-event-id";
+
+/// Sessions created by the Streamable HTTP transport, keyed by session ID
+#[derive(Clone)]
+pub struct HttpSessions {
+    sessions: Arc<RwLock<HashMap<String, Arc<HttpSession>>>>,
+    /// Most sessions open at once, any number if zero
+    max_sessions: usize,
+}
+
+// A single Streamable HTTP session
+struct HttpSession {
+    /// The token that started the session, the only one that may use it
+    token: TokenId,
+    /// When the client last sent a request or closed its GET stream
+    last_active: Mutex<Instant>,
+    /// Sender for notifications that aren't tied to a POST request
+    notify_tx: mpsc::UnboundedSender<Value>,
+    /// Receiving end, held by the open GET stream (if any)
+    notify_rx: Mutex<Option<mpsc::UnboundedReceiver<Value>>>,
+}
+
+// Hands the notification receiver back to its session when a GET stream closes,
+// so the client can open a new one and pick up anything queued in between
+struct SessionReceiver {
+    session: Arc<HttpSession>,
+    rx: Option<mpsc::UnboundedReceiver<Value>>,
+}
+
+impl Drop for SessionReceiver {
+    fn drop(&mut self) {
+        if let Ok(mut slot) = self.session.notify_rx.lock() {
+            *slot = self.rx.take();
+        }
+        self.session.touch();
+    }
+}
+
+impl HttpSession {
+    // Note that the client is still around
+    fn touch(&self) {
+        if let Ok(mut last_active) = self.last_active.lock() {
+            *last_active = Instant::now();
+        }
+    }
+
+    // Whether the client has neither a GET stream open nor sent anything
+    // for `timeout`
+    fn is_idle(&self, timeout: Duration) -> bool {
+        let streaming = self
+            .notify_rx
+            .lock()
+            .map(|slot| slot.is_none())
+            .unwrap_or(false);
+        let last_active = self
+            .last_active
+            .lock()
+            .map(|last_active| *last_active)
+            .unwrap_or_else(|_| Instant::now());
+
+        !streaming && last_active.elapsed() >= timeout
+    }
+}
+
+impl HttpSessions {
+    /// Track sessions, allowing at most `max_sessions` at once, or any number
+    /// if zero
+    pub fn new(max_sessions: usize) -> Self {
+        Self {
+            sessions: Arc::default(),
+            max_sessions,
+        }
+    }
+
+    /// Close sessions idle for `timeout` in the background, for as long as
+    /// the sessions are in use; a zero timeout keeps them until deleted
+    pub fn spawn_reaper(&self, state: Arc<RwLock<ServerState>>, timeout: Duration) {
+        if timeout.is_zero() {
+            return;
+        }
+
+        let sessions = Arc::downgrade(&self.sessions);
+        let period = (timeout / 2).max(Duration::from_millis(100));
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(period);
+            loop {
+                interval.tick().await;
+                let Some(sessions) = sessions.upgrade() else {
+                    break;
+                };
+
+                let mut expired = Vec::new();
+                sessions.write().await.retain(|session_id, session| {
+                    let idle = session.is_idle(timeout);
+                    if idle {
+                        expired.push(session_id.clone());
+                    }
+                    !idle
+                });
+                if expired.is_empty() {
+                    continue;
+                }
+
+                let mut state = state.write().await;
+                for session_id in expired {
+                    forget_client(&mut state, &session_id, DisconnectReason::IdleTimeout);
+                    info!("Streamable HTTP session expired: {}", session_id);
+                }
+            }
+        });
+    }
+
+    // Create a new session and return its ID, unless there are too many
+    async fn create(&self, token: TokenId) -> Option<(String, Arc<HttpSession>)> {
+        let mut sessions = self.sessions.write().await;
+        if self.max_sessions > 0 && sessions.len() >= self.max_sessions {
+            return None;
+        }
+
+        let (notify_tx, notify_rx) = mpsc::unbounded_channel();
+        let session = Arc::new(HttpSession {
+            token,
+            last_active: Mutex::new(Instant::now()),
+            notify_tx,
+            notify_rx: Mutex::new(Some(notify_rx)),
+        });
+        let session_id = uuid::Uuid::new_v4().to_string();
+        sessions.insert(session_id.clone(), session.clone());
+
+        Some((session_id, session))
+    }
+
+    // Look up the session named in the request headers
+    async fn lookup(&self, headers: &HeaderMap) -> Result<(String, Arc<HttpSession>), StatusCode> {
+        let session_id = headers
+            .get(SESSION_HEADER)
+            .and_then(|value| value.to_str().ok())
+            .ok_or(StatusCode::BAD_REQUEST)?;
+
+        let session = self
+            .sessions
+            .read()
+            .await
+            .get(session_id)
+            .cloned()
+            .ok_or(StatusCode::NOT_FOUND)?;
+        session.touch();
+
+        Ok((session_id.to_string(), session))
+    }
+
+    // Remove a session
+    async fn remove(&self, session_id: &str) {
+        self.sessions.write().await.remove(session_id);
+    }
+
+    // Remove a session that failed to initialize, along with its client
+    async fn discard(&self, state: &RwLock<ServerState>, session_id: &str) {
+        self.remove(session_id).await;
+        state.write().await.remove_client(session_id);
+        info!(
+            "Streamable HTTP session failed to initialize: {}",
+            session_id
+        );
+    }
+}
+
+// Drop a closed session's client from the server state
+fn forget_client(state: &mut ServerState, session_id: &str, reason: DisconnectReason) {
+    state.record_disconnect(session_id, reason);
+    state.cancel_client_requests(session_id);
+    state.remove_client(session_id);
+}
+
+// Whether the reply to `initialize` is an error, leaving the session unusable
+fn is_failure(reply: Option<&JsonRpcReply>) -> bool {
+    match reply {
+        Some(JsonRpcReply::Single(response)) => response.error.is_some(),
+        _ => true,
+    }
+}
+
+// Refuse a request unless it carries the token that started the session:
+// session IDs are no secret from other clients of the server
+fn check_session_access(
+    token: &TokenId,
+    session_id: &str,
+    session: &HttpSession,
+) -> Result<(), McpError> {
+    if *token == session.token {
+        return Ok(());
+    }
+
+    warn!("Denied access to session {} with another token", session_id);
+    Err(McpError::Forbidden(
+        "the session belongs to another token".to_string(),
+    ))
+}
+
+// Whether the client is willing to receive an SSE stream
+fn accepts_event_stream(headers: &HeaderMap) -> bool {
+    headers
+        .get(header::ACCEPT)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|accept| accept.contains("text/event-stream"))
+}
+
+// Whether a message is a notification or a client response, which get no reply
+fn is_one_way(message: &Value) -> bool {
+    let has_id = message.get("id").is_some();
+    let has_method = message.get("method").is_some();
+    let has_outcome = message.get("result").is_some() || message.get("error").is_some();
+
+    (has_method && !has_id) || (!has_method && has_outcome)
+}
+
+// Attach the session header to a response
+fn with_session(mut response: Response, session_id: &str) -> Response {
+    if let Ok(value) = HeaderValue::from_str(session_id) {
+        response.headers_mut().insert(SESSION_HEADER, value);
+    }
+    response
+}
+
+// Serialize a JSON-RPC message as an SSE event
+fn sse_event(message: &Value) -> Result<Event, Infallible> {
+    Ok(Event::default().data(message.to_string()))
+}
+
+// Serialize a server event as an SSE event carrying its sequence ID
+fn sse_delivery(delivery: &EventDelivery) -> Result<Event, Infallible> {
+    let event = Event::default().data(json!(delivery.to_notification()).to_string());
+    Ok(match delivery {
+        EventDelivery::Event(event_data) => event.id(event_data.id.to_string()),
+        EventDelivery::Dropped { .. } => event,
+    })
+}
+
+// Handle a JSON-RPC message POSTed by the client
+pub async fn post_handler(
+    Extension(state): Extension<Arc<RwLock<ServerState>>>,
+    Extension(event_tx): Extension<EventBus>,
+    Extension(sessions): Extension<HttpSessions>,
+    Extension(Granted(scopes)): Extension<Granted>,
+    Extension(token): Extension<TokenId>,
+    headers: HeaderMap,
+    body: String,
+) -> Response {
+    let message: Value = match serde_json::from_str(&body) {
+        Ok(message) => message,
+        Err(e) => {
+            let response =
+                JsonRpcResponse::failure(Value::Null, JsonRpcError::parse_error(e.to_string()));
+            return (StatusCode::BAD_REQUEST, Json(response)).into_response();
+        }
+    };
+
+    let messages: Vec<&Value> = match &message {
+        Value::Array(batch) => batch.iter().collect(),
+        message => vec![message],
+    };
+    let has_method = messages
+        .iter()
+        .any(|message| message.get("method").is_some());
+    let needs_reply = messages.is_empty() || messages.iter().any(|message| !is_one_way(message));
+
+    // `initialize` starts a new session; everything else must name an existing one
+    let is_initialize = message.get("method").and_then(Value::as_str) == Some("initialize");
+    let (session_id, session) = if is_initialize {
+        let Some((session_id, session)) = sessions.create(token).await else {
+            warn!("Refused a Streamable HTTP session: too many open sessions");
+            let error = JsonRpcError::new(jsonrpc::SERVER_ERROR, "Too many open sessions");
+            let id = message.get("id").cloned().unwrap_or(Value::Null);
+            let response = JsonRpcResponse::failure(id, error);
+            return (StatusCode::SERVICE_UNAVAILABLE, Json(response)).into_response();
+        };
+        state.write().await.add_client(
+            ClientInfo::new(session_id.clone()).with_scopes(scopes.clone()),
+            session.notify_tx.clone(),
+        );
+        info!("New Streamable HTTP session: {}", session_id);
+        (session_id, session)
+    } else {
+        let (session_id, session) = match sessions.lookup(&headers).await {
+            Ok(found) => found,
+            Err(status) => return status.into_response(),
+        };
+        if let Err(e) = check_session_access(&token, &session_id, &session) {
+            return e.into_response();
+        }
+        (session_id, session)
+    };
+
+    let project_dir = state.read().await.project_dir().to_path_buf();
+    let mut ctx = RpcContext {
+        client_id: session_id.clone(),
+        state,
+        event_tx,
+        project_dir,
+        outbound: session.notify_tx.clone(),
+        progress_token: None,
+        // The scopes of the token that started the session, as checked above
+        granted: scopes,
+    };
+
+    // Notifications and client responses are acknowledged without a body
+    if !needs_reply {
+        if has_method {
+            handlers::rpc::handle_message(&ctx, &body).await;
+        }
+        return with_session(StatusCode::ACCEPTED.into_response(), &session_id);
+    }
+
+    if !accepts_event_stream(&headers) {
+        let reply = handlers::rpc::handle_message(&ctx, &body).await;
+        if is_initialize && is_failure(reply.as_ref()) {
+            sessions.discard(&ctx.state, &session_id).await;
+            return match reply {
+                Some(reply) => Json(reply).into_response(),
+                None => StatusCode::ACCEPTED.into_response(),
+            };
+        }
+        return match reply {
+            Some(reply) => with_session(Json(reply).into_response(), &session_id),
+            None => with_session(StatusCode::ACCEPTED.into_response(), &session_id),
+        };
+    }
+
+    // Stream notifications about this request, then the response, over SSE
+    let (request_tx, request_rx) = mpsc::unbounded_channel();
+    let (response_tx, response_rx) = oneshot::channel();
+    ctx.outbound = request_tx;
+
+    tokio::spawn(async move {
+        let reply = handlers::rpc::handle_message(&ctx, &body).await;
+        if is_initialize && is_failure(reply.as_ref()) {
+            sessions.discard(&ctx.state, &ctx.client_id).await;
+        }
+        if let Some(reply) = reply {
+            let _ = response_tx.send(json!(reply));
+        }
+    });
+
+    let stream = stream::unfold(Some((request_rx, response_rx)), |streams| async move {
+        let (mut request_rx, mut response_rx) = streams?;
+        tokio::select! {
+            biased;
+            Some(message) = request_rx.recv() => {
+                Some((sse_event(&message), Some((request_rx, response_rx))))
+            }
+            response = &mut response_rx => {
+                // The stream ends once the response has been sent
+                Some((sse_event(&response.ok()?), None))
+            }
+        }
+    });
+
+    with_session(Sse::new(stream).into_response(), &session_id)
+}
+
+// Open the SSE stream for server-to-client notifications and subscribed events
+pub async fn get_handler(
+    Extension(state): Extension<Arc<RwLock<ServerState>>>,
+    Extension(event_tx): Extension<EventBus>,
+    Extension(sessions): Extension<HttpSessions>,
+    Extension(token): Extension<TokenId>,
+    headers: HeaderMap,
+) -> Response {
+    if !accepts_event_stream(&headers) {
+        return StatusCode::NOT_ACCEPTABLE.into_response();
+    }
+
+    let (session_id, session) = match sessions.lookup(&headers).await {
+        Ok(found) => found,
+        Err(status) => return status.into_response(),
+    };
+    if let Err(e) = check_session_access(&token, &session_id, &session) {
+        return e.into_response();
+    }
+
+    // Only one GET stream per session may be open at a time
+    let rx = match session.notify_rx.lock() {
+        Ok(mut slot) => slot.take(),
+        Err(_) => None,
+    };
+    let Some(rx) = rx else {
+        return StatusCode::CONFLICT.into_response();
+    };
+
+    debug!("Opened SSE stream for session {}", session_id);
+
+    let receiver = SessionReceiver {
+        session,
+        rx: Some(rx),
+    };
+
+    // Resume after the last event the client saw on a previous stream
+    let last_event_id = headers
+        .get(LAST_EVENT_ID_HEADER)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.parse().ok());
+    let events = match last_event_id {
+        Some(last_event_id) => event_tx.subscribe_after(last_event_id),
+        None => event_tx.subscribe(),
+    };
+
+    with_session(
+        Sse::new(notification_stream(
+            state,
+            session_id.clone(),
+            receiver,
+            events,
+        ))
+        .keep_alive(KeepAlive::default())
+        .into_response(),
+        &session_id,
+    )
+}
+
+// Merge session notifications and broadcast events into one SSE stream
+fn notification_stream(
+    state: Arc<RwLock<ServerState>>,
+    session_id: String,
+    receiver: SessionReceiver,
+    events: EventCursor,
+) -> impl Stream<Item = Result<Event, Infallible>> {
+    stream::unfold(
+        (receiver, events, state, session_id),
+        |(mut receiver, mut events, state, session_id)| async move {
+            loop {
+                let rx = receiver.rx.as_mut()?;
+                let event = tokio::select! {
+                    message = rx.recv() => sse_event(&message?),
+                    delivery = events.next() => match delivery? {
+                        delivery if state.read().await.wants_delivery(&session_id, &delivery) => {
+                            sse_delivery(&delivery)
+                        }
+                        _ => continue,
+                    },
+                };
+
+                return Some((event, (receiver, events, state, session_id)));
+            }
+        },
+    )
+}
+
+// Terminate a session
+pub async fn delete_handler(
+    Extension(state): Extension<Arc<RwLock<ServerState>>>,
+    Extension(sessions): Extension<HttpSessions>,
+    Extension(token): Extension<TokenId>,
+    headers: HeaderMap,
+) -> Response {
+    let (session_id, session) = match sessions.lookup(&headers).await {
+        Ok(found) => found,
+        Err(status) => return status.into_response(),
+    };
+    if let Err(e) = check_session_access(&token, &session_id, &session) {
+        return e.into_response();
+    }
+
+    sessions.remove(&session_id).await;
+    forget_client(
+        &mut *state.write().await,
+        &session_id,
+        DisconnectReason::ClientClosed,
+    );
+
+    info!("Streamable HTTP session closed: {}", session_id);
+
+    StatusCode::NO_CONTENT.into_response()
+}
@@ -0,0 +1,213 @@
+// Bearer-token authentication for the HTTP and WebSocket endpoints
+
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use axum::{
+    extract::State,
+    http::{header, HeaderValue, Request},
+    middleware::Next,
+    response::{IntoResponse, Json, Response},
+};
+use log::warn;
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+
+use crate::api::auth::{Scope, Scopes};
+use crate::api::jsonrpc::{JsonRpcError, JsonRpcResponse};
+use crate::server::ServerOptions;
+use crate::utils::error::McpError;
+use crate::utils::file_utils;
+
+/// Query parameter carrying the token on WebSocket upgrades, for browsers
+/// that can't set headers on them
+pub const TOKEN_QUERY_PARAM: &str = "access_token";
+
+/// Generate a random token
+pub fn generate_token() -> String {
+    // Two v4 UUIDs give 244 random bits
+    format!(
+        "{}{}",
+        uuid::Uuid::new_v4().simple(),
+        uuid::Uuid::new_v4().simple()
+    )
+}
+
+/// Where the token of the server listening on `port` is written by default,
+/// `~/.orbit/mcp-<port>.token`
+pub fn default_token_path(port: u16) -> PathBuf {
+    file_utils::orbit_dir().join(format!("mcp-{}.token", port))
+}
+
+/// Where a scoped token named `name` is written, next to the main token file
+pub fn scoped_token_path(token_file: &Path, name: &str) -> PathBuf {
+    let stem = token_file
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default();
+    token_file.with_file_name(format!("{}-{}.token", stem, name))
+}
+
+/// Write a token to a file only the current user can read
+pub fn write_token_file(path: &Path, token: &str) -> std::io::Result<()> {
+    file_utils::write_private_file(path, format!("{}\n", token).as_bytes())
+}
+
+/// Read a token written by [`write_token_file`]
+pub fn read_token_file(path: &Path) -> std::io::Result<String> {
+    Ok(std::fs::read_to_string(path)?.trim().to_string())
+}
+
+/// Scopes granted to an HTTP request, added to its extensions by [`authorize`]
+#[derive(Debug, Clone)]
+pub struct Granted(pub Scopes);
+
+impl Granted {
+    /// Fail unless the request may use operations that need `scope`
+    pub fn require(&self, scope: Scope) -> Result<(), McpError> {
+        require(&self.0, scope)
+    }
+}
+
+/// The token an HTTP request carries, added to its extensions by
+/// [`authorize`]
+///
+/// Only a digest is kept. All requests carry the same one when the server
+/// requires no token.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TokenId(Option<[u8; 32]>);
+
+impl TokenId {
+    fn of(token: Option<&str>) -> Self {
+        Self(token.map(|token| Sha256::digest(token.as_bytes()).into()))
+    }
+}
+
+/// Fail unless `scopes` allow operations that need `scope`
+pub fn require(scopes: &Scopes, scope: Scope) -> Result<(), McpError> {
+    if scopes.allows(scope) {
+        return Ok(());
+    }
+
+    warn!(
+        "Denied an operation needing the {} scope (granted: {})",
+        scope, scopes
+    );
+    Err(McpError::Forbidden(format!("requires the {} scope", scope)))
+}
+
+/// Middleware granting each request the scopes of the token it carries
+///
+/// The token is sent as `Authorization: Bearer <token>`, or as the
+/// `access_token` query parameter of a WebSocket upgrade. Requests without a
+/// valid token are rejected when the server requires one.
+pub async fn authorize<B>(
+    State(options): State<Arc<ServerOptions>>,
+    mut request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let (scopes, token) = if options.requires_token() {
+        let presented = bearer_token(&request).or_else(|| query_token(&request));
+        let granted = presented
+            .as_deref()
+            .and_then(|presented| token_scopes(&options, presented));
+
+        match granted {
+            Some(scopes) => (scopes, TokenId::of(presented.as_deref())),
+            None => {
+                let reason = if presented.is_some() {
+                    "invalid token"
+                } else {
+                    "missing token"
+                };
+                warn!(
+                    "Rejected {} {}: {}",
+                    request.method(),
+                    request.uri().path(),
+                    reason
+                );
+                return unauthorized(reason);
+            }
+        }
+    } else {
+        (Scopes::all(), TokenId::of(None))
+    };
+
+    let scopes = if options.read_only {
+        scopes.read_only()
+    } else {
+        scopes
+    };
+    request.extensions_mut().insert(Granted(scopes));
+    request.extensions_mut().insert(token);
+    next.run(request).await
+}
+
+// The scopes a token grants, if the server accepts it
+fn token_scopes(options: &ServerOptions, presented: &str) -> Option<Scopes> {
+    let admin = options
+        .auth_token
+        .iter()
+        .map(|token| (token, Scopes::all()));
+    let scoped = options
+        .scoped_tokens
+        .iter()
+        .map(|(token, scopes)| (token, scopes.clone()));
+
+    admin
+        .chain(scoped)
+        .find(|(token, _)| constant_time_eq(presented.as_bytes(), token.as_bytes()))
+        .map(|(_, scopes)| scopes)
+}
+
+// The token from the `Authorization` header
+fn bearer_token<B>(request: &Request<B>) -> Option<String> {
+    let value = request
+        .headers()
+        .get(header::AUTHORIZATION)?
+        .to_str()
+        .ok()?;
+    let (scheme, token) = value.split_once(' ')?;
+    scheme
+        .eq_ignore_ascii_case("bearer")
+        .then(|| token.trim().to_string())
+}
+
+// The token from the query string of a WebSocket upgrade
+fn query_token<B>(request: &Request<B>) -> Option<String> {
+    let upgrade = request
+        .headers()
+        .get(header::UPGRADE)
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.eq_ignore_ascii_case("websocket"));
+    if !upgrade {
+        return None;
+    }
+
+    request
+        .uri()
+        .query()?
+        .split('&')
+        .filter_map(|pair| pair.split_once('='))
+        .find(|(name, _)| *name == TOKEN_QUERY_PARAM)
+        .map(|(_, value)| value.to_string())
+}
+
+// Compare without returning early, so timing doesn't reveal the token
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+// A 401 carrying a JSON-RPC error, so MCP clients can report it like any other
+fn unauthorized(reason: &str) -> Response {
+    let error = McpError::Unauthorized(reason.to_string());
+    let status = error.status_code();
+    let body = JsonRpcResponse::failure(Value::Null, JsonRpcError::from(error));
+
+    let mut response = (status, Json(json!(body))).into_response();
+    response.headers_mut().insert(
+        header::WWW_AUTHENTICATE,
+        HeaderValue::from_static("Bearer realm=\"orbit-mcp\""),
+    );
+    response
+}
@@ -1,7 +1,11 @@
 // Error handling utilities
 
+use axum::response::{IntoResponse, Json, Response};
+use serde_json::json;
 use thiserror::Error;
 
+use crate::api::jsonrpc::{self, JsonRpcError};
+
 #[derive(Error, Debug)]
 pub enum McpError {
     #[error("Component not found: {0}")]
@@ -10,6 +14,9 @@ pub enum McpError {
     #[error("Project not found: {0}")]
     ProjectNotFound(String),
 
+    #[error("Resource not found: {0}")]
+    ResourceNotFound(String),
+
     #[error("Build error: {0}")]
     BuildError(String),
 
@@ -27,6 +34,18 @@ pub enum McpError {
 
     #[error("Server error: {0}")]
     ServerError(String),
+
+    #[error("Client error: {0}")]
+    ClientError(String),
+
+    #[error("Unauthorized: {0}")]
+    Unauthorized(String),
+
+    #[error("Forbidden: {0}")]
+    Forbidden(String),
+
+    #[error("Path not allowed: {0}")]
+    PathNotAllowed(String),
 }
 
 // Implement conversion from McpError to HTTP status code
@@ -37,8 +56,39 @@ impl McpError {
         match self {
             McpError::ComponentNotFound(_) => StatusCode::NOT_FOUND,
             McpError::ProjectNotFound(_) => StatusCode::NOT_FOUND,
+            McpError::ResourceNotFound(_) => StatusCode::NOT_FOUND,
             McpError::InvalidRequest(_) => StatusCode::BAD_REQUEST,
+            McpError::Unauthorized(_) => StatusCode::UNAUTHORIZED,
+            McpError::Forbidden(_) => StatusCode::FORBIDDEN,
+            McpError::PathNotAllowed(_) => StatusCode::FORBIDDEN,
             _ => StatusCode::INTERNAL_SERVER_ERROR,
         }
     }
+
+    pub fn jsonrpc_code(&self) -> i64 {
+        match self {
+            McpError::ComponentNotFound(_) => jsonrpc::NOT_FOUND,
+            McpError::ProjectNotFound(_) => jsonrpc::NOT_FOUND,
+            McpError::ResourceNotFound(_) => jsonrpc::RESOURCE_NOT_FOUND,
+            McpError::InvalidRequest(_) => jsonrpc::INVALID_PARAMS,
+            McpError::PathNotAllowed(_) => jsonrpc::INVALID_PARAMS,
+            McpError::Unauthorized(_) => jsonrpc::UNAUTHORIZED,
+            McpError::Forbidden(_) => jsonrpc::FORBIDDEN,
+            McpError::IoError(_) | McpError::ServerError(_) => jsonrpc::INTERNAL_ERROR,
+            _ => jsonrpc::SERVER_ERROR,
+        }
+    }
+}
+
+impl IntoResponse for McpError {
+    fn into_response(self) -> Response {
+        let status = self.status_code();
+        (status, Json(json!({ "error": self.to_string() }))).into_response()
+    }
+}
+
+impl From<McpError> for JsonRpcError {
+    fn from(error: McpError) -> Self {
+        JsonRpcError::new(error.jsonrpc_code(), error.to_string())
+    }
 }
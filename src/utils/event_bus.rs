@@ -0,0 +1,211 @@
+// Sequenced event broadcasting with replay
+
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+use serde_json::json;
+use tokio::sync::broadcast;
+use tokio::sync::broadcast::error::RecvError;
+
+use crate::api::jsonrpc::JsonRpcNotification;
+use crate::server::ServerEvent;
+
+/// Number of events a slow receiver may fall behind before it lags
+pub const EVENT_CHANNEL_CAPACITY: usize = 256;
+
+/// Number of recent events kept for replay
+pub const EVENT_HISTORY_SIZE: usize = 1024;
+
+/// Broadcasts server events with sequence IDs and keeps recent ones for replay
+#[derive(Clone)]
+pub struct EventBus {
+    /// Live event channel
+    tx: broadcast::Sender<ServerEvent>,
+    /// Recently sent events and the next sequence ID
+    history: Arc<Mutex<EventHistory>>,
+}
+
+// Ring buffer of recently sent events
+struct EventHistory {
+    /// Sequence ID for the next event
+    next_id: u64,
+    /// Most recent events, oldest first
+    events: VecDeque<ServerEvent>,
+    /// Maximum number of events to keep
+    capacity: usize,
+}
+
+impl EventHistory {
+    // Events after `last_event_id`, and how many of those are no longer kept
+    fn since(&self, last_event_id: u64) -> (VecDeque<ServerEvent>, u64) {
+        let oldest = self.events.front().map_or(self.next_id, |event| event.id);
+        let missed = oldest.saturating_sub(last_event_id.saturating_add(1));
+        let events = self
+            .events
+            .iter()
+            .filter(|event| event.id > last_event_id)
+            .cloned()
+            .collect();
+        (events, missed)
+    }
+}
+
+impl EventBus {
+    /// Create a bus with the given channel capacity and replay history size
+    pub fn new(capacity: usize, history: usize) -> Self {
+        let (tx, _) = broadcast::channel(capacity);
+        Self {
+            tx,
+            history: Arc::new(Mutex::new(EventHistory {
+                next_id: 1,
+                events: VecDeque::with_capacity(history),
+                capacity: history,
+            })),
+        }
+    }
+
+    /// Assign the next sequence ID to an event and broadcast it
+    pub fn send(&self, mut event: ServerEvent) -> u64 {
+        let mut history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+
+        event.id = history.next_id;
+        history.next_id += 1;
+
+        if history.events.len() == history.capacity {
+            history.events.pop_front();
+        }
+        history.events.push_back(event.clone());
+
+        // Sent under the lock so receivers see events in sequence order
+        let _ = self.tx.send(event);
+
+        history.next_id - 1
+    }
+
+    /// ID of the most recently sent event, or 0 if there was none
+    pub fn last_event_id(&self) -> u64 {
+        self.history
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .next_id
+            - 1
+    }
+
+    /// Receive events sent from now on
+    pub fn subscribe(&self) -> EventCursor {
+        let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        EventCursor {
+            bus: self.clone(),
+            rx: self.tx.subscribe(),
+            last_id: history.next_id - 1,
+            backlog: VecDeque::new(),
+            missed: 0,
+        }
+    }
+
+    /// Receive the events sent after `last_event_id`, then new ones
+    pub fn subscribe_after(&self, last_event_id: u64) -> EventCursor {
+        let history = self.history.lock().unwrap_or_else(|e| e.into_inner());
+        let last_event_id = last_event_id.min(history.next_id - 1);
+        let (backlog, missed) = history.since(last_event_id);
+
+        EventCursor {
+            bus: self.clone(),
+            rx: self.tx.subscribe(),
+            last_id: last_event_id,
+            backlog,
+            missed,
+        }
+    }
+}
+
+/// Something to deliver to an event receiver
+#[derive(Debug, Clone)]
+pub enum EventDelivery {
+    /// The next event
+    Event(ServerEvent),
+    /// Events that were lost because the receiver fell too far behind
+    Dropped {
+        /// Number of lost events
+        count: u64,
+        /// ID of the last event delivered before the gap
+        last_event_id: u64,
+    },
+}
+
+impl EventDelivery {
+    /// Build the notification sent to clients
+    pub fn to_notification(&self) -> JsonRpcNotification {
+        match self {
+            EventDelivery::Event(event) => event.to_notification(),
+            EventDelivery::Dropped {
+                count,
+                last_event_id,
+            } => JsonRpcNotification::new(
+                "server.eventsDropped",
+                json!({ "count": count, "lastEventId": last_event_id }),
+            ),
+        }
+    }
+}
+
+/// A single receiver's position in the event sequence
+///
+/// When the receiver lags behind the live channel, the missed events are
+/// recovered from the replay history. Only events that have left the history
+/// as well are reported as dropped.
+pub struct EventCursor {
+    /// Bus the cursor reads from
+    bus: EventBus,
+    /// Live event channel
+    rx: broadcast::Receiver<ServerEvent>,
+    /// ID of the last event delivered
+    last_id: u64,
+    /// Replayed events to deliver before reading the channel again
+    backlog: VecDeque<ServerEvent>,
+    /// Number of lost events still to report
+    missed: u64,
+}
+
+impl EventCursor {
+    /// Wait for the next event, or `None` once the bus is gone
+    ///
+    /// This is cancel-safe, so it can be used in `tokio::select!`.
+    pub async fn next(&mut self) -> Option<EventDelivery> {
+        loop {
+            if self.missed > 0 {
+                let count = std::mem::take(&mut self.missed);
+                let last_event_id = self.last_id;
+                self.last_id += count;
+                return Some(EventDelivery::Dropped {
+                    count,
+                    last_event_id,
+                });
+            }
+
+            if let Some(event) = self.backlog.pop_front() {
+                if event.id > self.last_id {
+                    self.last_id = event.id;
+                    return Some(EventDelivery::Event(event));
+                }
+                continue;
+            }
+
+            match self.rx.recv().await {
+                // Replayed events come around again on the live channel
+                Ok(event) if event.id <= self.last_id => continue,
+                Ok(event) => {
+                    self.last_id = event.id;
+                    return Some(EventDelivery::Event(event));
+                }
+                Err(RecvError::Lagged(_)) => {
+                    let history = self.bus.history.lock().unwrap_or_else(|e| e.into_inner());
+                    let (backlog, missed) = history.since(self.last_id);
+                    self.backlog = backlog;
+                    self.missed = missed;
+                }
+                Err(RecvError::Closed) => return None,
+            }
+        }
+    }
+}
@@ -0,0 +1,78 @@
+// Event subscription filters
+
+use globset::{Glob, GlobBuilder, GlobSet, GlobSetBuilder};
+use std::path::{Path, PathBuf};
+
+use crate::server::ServerEvent;
+use crate::utils::error::McpError;
+
+/// Which server events a client subscribed to
+pub struct EventFilter {
+    /// Patterns for event types, such as `build.*`
+    events: GlobSet,
+    /// Globs for the `path` of file events, relative to the project root
+    paths: Option<GlobSet>,
+    /// Project root that relative path globs are matched against
+    project_root: PathBuf,
+}
+
+impl EventFilter {
+    /// Compile the given event type patterns and optional path globs
+    pub fn new(events: &[String], paths: &[String], project_root: &Path) -> Result<Self, McpError> {
+        if events.is_empty() {
+            return Err(McpError::InvalidRequest(
+                "At least one event pattern is required".to_string(),
+            ));
+        }
+
+        let mut builder = GlobSetBuilder::new();
+        for pattern in events {
+            builder.add(Glob::new(pattern).map_err(invalid_pattern)?);
+        }
+        let events = builder.build().map_err(invalid_pattern)?;
+
+        let paths = if paths.is_empty() {
+            None
+        } else {
+            let mut builder = GlobSetBuilder::new();
+            for pattern in paths {
+                // `*` stays within one directory, `**` crosses them
+                let glob = GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .map_err(invalid_pattern)?;
+                builder.add(glob);
+            }
+            Some(builder.build().map_err(invalid_pattern)?)
+        };
+
+        Ok(Self {
+            events,
+            paths,
+            project_root: project_root.to_path_buf(),
+        })
+    }
+
+    /// Whether an event passes the filter
+    ///
+    /// Path globs only apply to events that carry a `path`; other events are
+    /// matched on their type alone.
+    pub fn matches(&self, event: &ServerEvent) -> bool {
+        if !self.events.is_match(&event.event_type) {
+            return false;
+        }
+
+        let (Some(paths), Some(path)) = (&self.paths, event.payload["path"].as_str()) else {
+            return true;
+        };
+
+        let path = Path::new(path);
+        let relative = path.strip_prefix(&self.project_root).unwrap_or(path);
+        paths.is_match(relative)
+    }
+}
+
+// Report a pattern that doesn't compile
+fn invalid_pattern(e: globset::Error) -> McpError {
+    McpError::InvalidRequest(format!("Invalid pattern: {}", e))
+}
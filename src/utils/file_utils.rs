@@ -1,27 +1,222 @@
 // File utility functions
 
 use anyhow::{Context, Result};
-use std::path::{Path, PathBuf};
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::io::Write;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+
+use crate::utils::error::McpError;
+
+/// Paths in a project the server never reads or writes for clients
+///
+/// Patterns without a `/` match a file or directory of that name anywhere in
+/// the project; denying a directory denies everything below it.
+pub const DEFAULT_DENIED_PATHS: &[&str] = &[
+    ".git",
+    "target",
+    ".env",
+    ".env.*",
+    ".ssh",
+    ".netrc",
+    ".npmrc",
+    "*.pem",
+    "*.key",
+    "id_rsa*",
+    "id_ed25519*",
+];
+
+/// A compiled deny-list, each server keeping its own
+#[derive(Debug, Clone)]
+pub struct DenyList {
+    denied: Arc<GlobSet>,
+    /// Canonical directories projects lie in, which patterns are relative to
+    bases: Arc<Vec<PathBuf>>,
+}
+
+impl DenyList {
+    /// Compile deny-list patterns, extending each to what lies below it
+    pub fn new<S: AsRef<str>>(patterns: &[S]) -> Result<Self> {
+        let mut builder = GlobSetBuilder::new();
+        for pattern in patterns {
+            let pattern = pattern.as_ref().trim_matches('/');
+            let anywhere = !pattern.contains('/');
+            let mut globs = vec![pattern.to_string(), format!("{}/**", pattern)];
+            if anywhere {
+                globs.push(format!("**/{}", pattern));
+                globs.push(format!("**/{}/**", pattern));
+            }
+
+            for glob in globs {
+                let glob = GlobBuilder::new(&glob)
+                    .literal_separator(true)
+                    .build()
+                    .with_context(|| format!("Invalid deny-list pattern {:?}", pattern))?;
+                builder.add(glob);
+            }
+        }
+        let denied = builder.build().context("Failed to build the deny-list")?;
+        Ok(Self {
+            denied: Arc::new(denied),
+            bases: Arc::new(Vec::new()),
+        })
+    }
+
+    /// Also match paths relative to these directories, such as the server's
+    /// project, so projects nested in them can't escape the deny-list
+    pub fn within<I, P>(mut self, dirs: I) -> Self
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        self.bases = Arc::new(
+            dirs.into_iter()
+                .filter_map(|dir| canonicalize_existing(dir.as_ref()).ok())
+                .collect(),
+        );
+        self
+    }
+
+    /// Whether a path relative to the project root is on the deny-list
+    pub fn is_denied(&self, relative: &Path) -> bool {
+        self.denied.is_match(relative)
+    }
+
+    /// Whether a canonical path is on the deny-list relative to any of the
+    /// directories it lies in
+    pub fn is_denied_path(&self, path: &Path) -> bool {
+        self.bases.iter().any(|base| {
+            path.strip_prefix(base)
+                .is_ok_and(|relative| self.is_denied(relative))
+        })
+    }
+}
+
+impl Default for DenyList {
+    fn default() -> Self {
+        Self::new(DEFAULT_DENIED_PATHS).expect("default deny-list patterns are valid")
+    }
+}
+
+/// Resolve a path a client gave to the file it names inside the project
+///
+/// Relative paths are taken from `project_dir`; absolute ones must already
+/// be inside it. Paths that climb out with `..`, lead out through a symlink
+/// or are on `denied`, relative to the project or to a directory it lies
+/// in, are rejected. The file doesn't need to exist yet.
+pub fn resolve_project_path<P: AsRef<Path>>(
+    project_dir: &Path,
+    denied: &DenyList,
+    path: P,
+) -> Result<PathBuf, McpError> {
+    let path = path.as_ref();
+    let not_allowed =
+        |reason: &str| McpError::PathNotAllowed(format!("{} ({})", path.display(), reason));
+
+    let root = canonicalize_existing(project_dir)
+        .map_err(|e| McpError::ProjectNotFound(format!("{}: {}", project_dir.display(), e)))?;
+
+    let relative = if path.is_absolute() {
+        path.strip_prefix(&root)
+            .or_else(|_| path.strip_prefix(project_dir))
+            .map_err(|_| not_allowed("outside the project"))?
+    } else {
+        path
+    };
+
+    let mut resolved = root.clone();
+    for component in relative.components() {
+        match component {
+            Component::Normal(part) => resolved.push(part),
+            Component::CurDir => {}
+            Component::ParentDir => return Err(not_allowed("climbs out with ..")),
+            Component::RootDir | Component::Prefix(_) => {
+                return Err(not_allowed("outside the project"))
+            }
+        }
+    }
+
+    // Symlinks in the part that exists may lead anywhere
+    let resolved = canonicalize_existing(&resolved).map_err(|e| match e.kind() {
+        std::io::ErrorKind::NotFound => not_allowed("dangling symlink"),
+        _ => McpError::IoError(e),
+    })?;
+    let inside = resolved
+        .strip_prefix(&root)
+        .map_err(|_| not_allowed("leads outside the project"))?;
+    if denied.is_denied(inside) || denied.is_denied_path(&resolved) {
+        return Err(not_allowed("denied"));
+    }
+
+    Ok(resolved)
+}
+
+/// Canonicalize the longest existing ancestor of a path and append the rest
+///
+/// Fails with `NotFound` for dangling symlinks, which would be followed
+/// wherever they point once the file is written.
+pub fn canonicalize_existing(path: &Path) -> std::io::Result<PathBuf> {
+    let mut existing = path;
+    let mut missing = Vec::new();
+    loop {
+        match existing.canonicalize() {
+            Ok(canonical) => {
+                return Ok(missing
+                    .iter()
+                    .rev()
+                    .fold(canonical, |path, part| path.join(part)))
+            }
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                if existing.symlink_metadata().is_ok() {
+                    return Err(e);
+                }
+                match (existing.file_name(), existing.parent()) {
+                    (Some(name), Some(parent)) => {
+                        missing.push(name.to_os_string());
+                        existing = parent;
+                    }
+                    _ => return Err(e),
+                }
+            }
+            Err(e) => return Err(e),
+        }
+    }
+}
 
 /// Find all component files in a directory
+///
+/// Hidden directories, Cargo `target` directories and symlinked directories
+/// are skipped.
 pub async fn find_component_files<P: AsRef<Path>>(dir: P, extension: &str) -> Result<Vec<PathBuf>> {
     let mut result = Vec::new();
-    let mut entries = tokio::fs::read_dir(dir).await?;
+    let mut pending = vec![dir.as_ref().to_path_buf()];
 
-    while let Some(entry) = entries.next_entry().await? {
-        let path = entry.path();
+    // Walk the tree with an explicit stack so the future stays `Send`
+    while let Some(dir) = pending.pop() {
+        let mut entries = tokio::fs::read_dir(&dir).await?;
 
-        if path.is_dir() {
-            // Recursively search subdirectories
-            let mut subdir_results = find_component_files(&path, extension).await?;
-            result.append(&mut subdir_results);
-        } else if let Some(ext) = path.extension() {
-            if ext == extension {
-                result.push(path);
+        while let Some(entry) = entries.next_entry().await? {
+            let path = entry.path();
+
+            // Symlinked directories may lead out of the project
+            if entry.file_type().await?.is_dir() {
+                let name = entry.file_name();
+                let name = name.to_string_lossy();
+                if name.starts_with('.') || name == "target" {
+                    continue;
+                }
+
+                // Search subdirectories too
+                pending.push(path);
+            } else if let Some(ext) = path.extension() {
+                if ext == extension {
+                    result.push(path);
+                }
             }
         }
     }
 
+    result.sort();
     Ok(result)
 }
 
@@ -33,6 +228,9 @@ pub async fn read_component_file<P: AsRef<Path>>(path: P) -> Result<String> {
 }
 
 /// Write a component file
+///
+/// The path must come from [`resolve_project_path`], or parent directories
+/// may be created anywhere.
 pub async fn write_component_file<P: AsRef<Path>>(path: P, content: &str) -> Result<()> {
     // Create parent directory if it doesn't exist
     if let Some(parent) = path.as_ref().parent() {
@@ -43,3 +241,76 @@ pub async fn write_component_file<P: AsRef<Path>>(path: P, content: &str) -> Res
         .await
         .context("Failed to write component file")
 }
+
+/// Directory the server keeps its tokens and certificates in, `~/.orbit`
+pub fn orbit_dir() -> PathBuf {
+    let home = std::env::var_os("HOME")
+        .or_else(|| std::env::var_os("USERPROFILE"))
+        .map(PathBuf::from)
+        .unwrap_or_else(std::env::temp_dir);
+    home.join(".orbit")
+}
+
+/// Write a file only the current user can read, such as a token or a
+/// private key
+pub fn write_private_file(path: &Path, contents: &[u8]) -> std::io::Result<()> {
+    if let Some(parent) = path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::{OpenOptionsExt, PermissionsExt};
+
+        options.mode(0o600);
+        // The mode only applies to new files, so tighten an existing one first
+        if path.exists() {
+            std::fs::set_permissions(path, std::fs::Permissions::from_mode(0o600))?;
+        }
+    }
+
+    let mut file = options.open(path)?;
+    file.write_all(contents)
+}
+
+/// Convert a `file://` URI, such as an MCP root, to a local path
+///
+/// Returns `None` for other schemes and for files on remote hosts.
+pub fn file_uri_to_path(uri: &str) -> Option<PathBuf> {
+    let rest = uri.strip_prefix("file://")?;
+    let path = rest.strip_prefix("localhost").unwrap_or(rest);
+    if !path.starts_with('/') {
+        return None;
+    }
+
+    // Percent-decode the path, byte by byte so multi-byte characters survive
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let escaped = (bytes[i] == b'%')
+            .then(|| bytes.get(i + 1..i + 3))
+            .flatten()
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match escaped {
+            Some(byte) => {
+                decoded.push(byte);
+                i += 3;
+            }
+            None => {
+                decoded.push(bytes[i]);
+                i += 1;
+            }
+        }
+    }
+
+    let path = String::from_utf8(decoded).ok()?;
+    // Windows drive paths come as `/C:/...`
+    let path = match path.as_bytes() {
+        [b'/', drive, b':', ..] if cfg!(windows) && drive.is_ascii_alphabetic() => &path[1..],
+        _ => &path,
+    };
+    Some(PathBuf::from(path))
+}
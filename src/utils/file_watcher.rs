@@ -0,0 +1,85 @@
+// File watching utilities
+
+use anyhow::{Context, Result};
+use log::{debug, warn};
+use notify::{EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use tokio::sync::mpsc;
+
+/// Watches individual files for changes on disk
+///
+/// Files are watched through their parent directory so that editors which save
+/// by writing a temporary file and renaming it over the original are noticed.
+pub struct FileWatcher {
+    /// Underlying platform watcher
+    watcher: RecommendedWatcher,
+    /// Number of watched files in each watched directory
+    directories: HashMap<PathBuf, usize>,
+}
+
+impl FileWatcher {
+    /// Create a watcher that reports changed file paths on the returned channel
+    pub fn new() -> Result<(Self, mpsc::UnboundedReceiver<PathBuf>)> {
+        let (tx, rx) = mpsc::unbounded_channel();
+
+        let watcher =
+            notify::recommended_watcher(move |res: notify::Result<notify::Event>| match res {
+                Ok(event) => {
+                    if matches!(
+                        event.kind,
+                        EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_)
+                    ) {
+                        for path in event.paths {
+                            let _ = tx.send(path);
+                        }
+                    }
+                }
+                Err(e) => warn!("File watcher error: {}", e),
+            })
+            .context("Failed to create file watcher")?;
+
+        Ok((
+            Self {
+                watcher,
+                directories: HashMap::new(),
+            },
+            rx,
+        ))
+    }
+
+    /// Start watching a file
+    pub fn watch(&mut self, file: &Path) -> Result<()> {
+        let dir = file
+            .parent()
+            .context("Cannot watch a path without a parent directory")?
+            .to_path_buf();
+
+        let count = self.directories.entry(dir.clone()).or_insert(0);
+        if *count == 0 {
+            self.watcher
+                .watch(&dir, RecursiveMode::NonRecursive)
+                .with_context(|| format!("Failed to watch {:?}", dir))?;
+            debug!("Watching directory {:?}", dir);
+        }
+        *count += 1;
+
+        Ok(())
+    }
+
+    /// Stop watching a file
+    pub fn unwatch(&mut self, file: &Path) {
+        let Some(dir) = file.parent() else {
+            return;
+        };
+
+        if let Some(count) = self.directories.get_mut(dir) {
+            *count -= 1;
+            if *count == 0 {
+                self.directories.remove(dir);
+                let _ = self.watcher.unwatch(dir);
+                debug!("Stopped watching directory {:?}", dir);
+            }
+        }
+    }
+}
@@ -0,0 +1,59 @@
+// Fuzzy matching for completions
+
+/// Rank candidates against what the user typed, best match first
+///
+/// Prefix matches come first, then matches anywhere in the candidate, then
+/// candidates that contain the typed characters in order (such as `usrpro`
+/// for `user-profile`). Matching ignores case; candidates that don't match
+/// at all are dropped. An empty query matches everything.
+pub fn rank<I>(candidates: I, query: &str) -> Vec<String>
+where
+    I: IntoIterator<Item = String>,
+{
+    let query = query.to_lowercase();
+    let mut scored: Vec<(Score, String)> = candidates
+        .into_iter()
+        .filter_map(|candidate| Some((score(&candidate, &query)?, candidate)))
+        .collect();
+
+    scored.sort_by(|(a, a_name), (b, b_name)| {
+        a.cmp(b)
+            .then_with(|| a_name.len().cmp(&b_name.len()))
+            .then_with(|| a_name.cmp(b_name))
+    });
+    scored.dedup_by(|(_, a), (_, b)| a == b);
+    scored.into_iter().map(|(_, candidate)| candidate).collect()
+}
+
+// How well a candidate matches; lower is better
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+enum Score {
+    /// The candidate starts with the query
+    Prefix,
+    /// The query appears in the candidate, at this position
+    Substring(usize),
+    /// The query's characters appear in order, spread over this many characters
+    Subsequence(usize),
+}
+
+// Score a candidate against a lowercase query
+fn score(candidate: &str, query: &str) -> Option<Score> {
+    let candidate = candidate.to_lowercase();
+    if candidate.starts_with(query) {
+        return Some(Score::Prefix);
+    }
+    if let Some(position) = candidate.find(query) {
+        return Some(Score::Substring(position));
+    }
+
+    // Match each query character as early as possible
+    let mut start = None;
+    let mut end = 0;
+    let mut chars = candidate.char_indices();
+    for wanted in query.chars() {
+        let (index, _) = chars.by_ref().find(|(_, c)| *c == wanted)?;
+        start.get_or_insert(index);
+        end = index;
+    }
+    Some(Score::Subsequence(end - start.unwrap_or(0) + 1))
+}
@@ -0,0 +1,140 @@
+// Logger that also forwards the server's log records to MCP clients
+
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::OnceLock;
+
+use log::{LevelFilter, Log, Metadata, Record, SetLoggerError};
+use serde_json::Value;
+use tokio::sync::broadcast;
+
+// Capacity of the channel carrying records to the servers
+const RECORD_CHANNEL_CAPACITY: usize = 1024;
+
+// Only the server's own records are forwarded; library records (such as the
+// WebSocket frames sent to forward them) would feed back into the stream
+const FORWARDED_TARGET: &str = env!("CARGO_CRATE_NAME");
+
+// Most verbose level any client asked for, as a `LevelFilter`
+static FORWARD_LEVEL: AtomicUsize = AtomicUsize::new(0);
+
+// Level the terminal logger was configured with
+static TERMINAL_LEVEL: OnceLock<LevelFilter> = OnceLock::new();
+
+static RECORDS: OnceLock<broadcast::Sender<LogRecord>> = OnceLock::new();
+
+tokio::task_local! {
+    static REQUEST: RequestScope;
+}
+
+/// A log record to forward to clients
+#[derive(Debug, Clone)]
+pub struct LogRecord {
+    /// Record level
+    pub level: log::Level,
+    /// Logger name, which is the module that logged the record
+    pub logger: String,
+    /// Formatted message
+    pub message: String,
+    /// Client whose message was being handled when the record was logged
+    pub client_id: Option<String>,
+    /// ID of that request
+    pub request_id: Option<Value>,
+}
+
+// The client message, and the request if it is one, a task is working on
+#[derive(Debug, Clone)]
+struct RequestScope {
+    client_id: String,
+    request_id: Option<Value>,
+}
+
+// Logs to the terminal through env_logger and copies records to clients
+struct ForwardingLogger {
+    terminal: env_logger::Logger,
+}
+
+impl Log for ForwardingLogger {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        self.terminal.enabled(metadata) || forwards(metadata)
+    }
+
+    fn log(&self, record: &Record) {
+        if self.terminal.enabled(record.metadata()) {
+            self.terminal.log(record);
+        }
+
+        if !forwards(record.metadata()) {
+            return;
+        }
+        let Some(records) = RECORDS.get() else {
+            return;
+        };
+
+        let scope = REQUEST.try_with(RequestScope::clone).ok();
+        // Nobody listening is fine, there may be no server running
+        let _ = records.send(LogRecord {
+            level: record.level(),
+            logger: record.target().to_string(),
+            message: record.args().to_string(),
+            client_id: scope.as_ref().map(|scope| scope.client_id.clone()),
+            request_id: scope.and_then(|scope| scope.request_id),
+        });
+    }
+
+    fn flush(&self) {
+        self.terminal.flush();
+    }
+}
+
+/// Install the logger built by `builder`, forwarding records to clients that
+/// ask for them with `logging/setLevel`
+pub fn init(mut builder: env_logger::Builder) -> Result<(), SetLoggerError> {
+    let terminal = builder.build();
+    let level = terminal.filter();
+
+    log::set_boxed_logger(Box::new(ForwardingLogger { terminal }))?;
+    let _ = TERMINAL_LEVEL.set(level);
+    log::set_max_level(level);
+    Ok(())
+}
+
+/// Receive the records forwarded to clients
+pub fn subscribe() -> broadcast::Receiver<LogRecord> {
+    RECORDS
+        .get_or_init(|| broadcast::channel(RECORD_CHANNEL_CAPACITY).0)
+        .subscribe()
+}
+
+/// Make sure records down to `level` are produced, now that a client wants them
+///
+/// The level is never lowered again; records nobody wants are dropped when
+/// they are forwarded.
+pub fn raise_forward_level(level: LevelFilter) {
+    let previous = FORWARD_LEVEL.fetch_max(level as usize, Ordering::Relaxed);
+    if (level as usize) > previous {
+        let terminal = TERMINAL_LEVEL.get().copied().unwrap_or(LevelFilter::Off);
+        log::set_max_level(terminal.max(level));
+    }
+}
+
+/// Run the handling of a client's message, tagging the records it logs with
+/// the client and, for requests, the request ID
+pub async fn scope<F: Future>(client_id: &str, request_id: Option<&Value>, future: F) -> F::Output {
+    let scope = RequestScope {
+        client_id: client_id.to_string(),
+        request_id: request_id.cloned(),
+    };
+    REQUEST.scope(scope, future).await
+}
+
+// Whether a record should be forwarded to clients
+fn forwards(metadata: &Metadata) -> bool {
+    let target = metadata.target();
+    let own = target == FORWARDED_TARGET
+        || target
+            .strip_prefix(FORWARDED_TARGET)
+            .is_some_and(|rest| rest.starts_with("::"));
+
+    own && (metadata.level() as usize) <= FORWARD_LEVEL.load(Ordering::Relaxed)
+}
@@ -1,4 +1,13 @@
 // Utility functions module
 
+pub mod auth;
 pub mod error;
+pub mod event_bus;
+pub mod event_filter;
 pub mod file_utils;
+pub mod file_watcher;
+pub mod fuzzy;
+pub mod logging;
+pub mod origin;
+pub mod progress;
+pub mod tls;
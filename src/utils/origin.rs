@@ -0,0 +1,174 @@
+// Origin and Host checks against cross-site requests and DNS rebinding
+
+use std::sync::Arc;
+
+use anyhow::{Context, Result};
+use axum::{
+    extract::State,
+    http::{header, HeaderName, HeaderValue, Method, Request},
+    middleware::Next,
+    response::{IntoResponse, Response},
+};
+use log::warn;
+use tower_http::cors::{AllowHeaders, AllowMethods, AllowOrigin, CorsLayer};
+
+use crate::server::ServerOptions;
+use crate::transport::streamable_http::{LAST_EVENT_ID_HEADER, SESSION_HEADER};
+use crate::utils::error::McpError;
+
+/// Allows any origin or host when given in place of one
+pub const ANY: &str = "*";
+
+/// Host names clients may address the server by unless configured otherwise
+pub const DEFAULT_ALLOWED_HOSTS: &[&str] = &["localhost", "127.0.0.1", "[::1]"];
+
+/// Methods browsers may use from an allowed origin unless configured otherwise
+pub const DEFAULT_ALLOWED_METHODS: &[&str] = &["GET", "POST", "PUT", "DELETE"];
+
+/// Request headers browsers may send from an allowed origin unless
+/// configured otherwise
+pub const DEFAULT_ALLOWED_HEADERS: &[&str] = &[
+    "authorization",
+    "content-type",
+    "accept",
+    SESSION_HEADER,
+    LAST_EVENT_ID_HEADER,
+];
+
+/// Build the CORS layer answering browsers from the configured allow-lists
+///
+/// Browsers on origins that aren't allowed get no CORS headers back, so they
+/// can neither read responses nor send preflighted requests.
+pub fn cors_layer(options: &ServerOptions) -> Result<CorsLayer> {
+    let origins = if options.allowed_origins.iter().any(|origin| origin == ANY) {
+        AllowOrigin::any()
+    } else {
+        let origins = options
+            .allowed_origins
+            .iter()
+            .map(|origin| {
+                HeaderValue::from_str(normalize_origin(origin).as_str())
+                    .with_context(|| format!("Invalid allowed origin {:?}", origin))
+            })
+            .collect::<Result<Vec<_>>>()?;
+        AllowOrigin::list(origins)
+    };
+
+    let methods = options
+        .allowed_methods
+        .iter()
+        .map(|method| {
+            Method::from_bytes(method.trim().to_ascii_uppercase().as_bytes())
+                .with_context(|| format!("Invalid allowed method {:?}", method))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    let headers = options
+        .allowed_headers
+        .iter()
+        .map(|name| {
+            HeaderName::from_bytes(name.trim().as_bytes())
+                .with_context(|| format!("Invalid allowed header {:?}", name))
+        })
+        .collect::<Result<Vec<_>>>()?;
+
+    Ok(CorsLayer::new()
+        .allow_origin(origins)
+        .allow_methods(AllowMethods::list(methods))
+        .allow_headers(AllowHeaders::list(headers))
+        .expose_headers([HeaderName::from_static(SESSION_HEADER)]))
+}
+
+/// Middleware rejecting requests addressed to a host or sent from an origin
+/// that isn't allowed
+///
+/// Checking `Host` stops DNS rebinding, where a page makes the browser
+/// believe the server belongs to the page's own site. Checking `Origin`
+/// stops other sites from driving the server through a visitor's browser,
+/// including over WebSocket upgrades, which CORS doesn't cover. Requests
+/// without an `Origin` don't come from a browser page and only need a
+/// valid `Host`.
+pub async fn check_origin<B>(
+    State(options): State<Arc<ServerOptions>>,
+    request: Request<B>,
+    next: Next<B>,
+) -> Response {
+    let host = request
+        .headers()
+        .get(header::HOST)
+        .and_then(|value| value.to_str().ok())
+        .map(str::to_string)
+        .or_else(|| {
+            request
+                .uri()
+                .authority()
+                .map(|authority| authority.to_string())
+        });
+    let origin = request
+        .headers()
+        .get(header::ORIGIN)
+        .map(|value| value.to_str().unwrap_or_default().to_string());
+
+    let rejection = match (&host, &origin) {
+        (None, _) => Some("no Host header".to_string()),
+        (Some(host), _) if !host_allowed(&options.allowed_hosts, host) => {
+            Some(format!("host {:?} is not allowed", host))
+        }
+        (_, Some(origin)) if !origin_allowed(&options.allowed_origins, origin) => {
+            Some(format!("origin {:?} is not allowed", origin))
+        }
+        _ => None,
+    };
+
+    match rejection {
+        Some(reason) => {
+            warn!(
+                "Rejected {} {}: {}",
+                request.method(),
+                request.uri().path(),
+                reason
+            );
+            McpError::Forbidden(reason).into_response()
+        }
+        None => next.run(request).await,
+    }
+}
+
+// Whether a `Host` header, with or without a port, names an allowed host
+fn host_allowed(allowed: &[String], host: &str) -> bool {
+    let name = host_name(host);
+    allowed
+        .iter()
+        .any(|allowed| allowed == ANY || host_name(allowed).eq_ignore_ascii_case(name))
+}
+
+// The host name of a `host[:port]` authority, keeping IPv6 brackets
+fn host_name(host: &str) -> &str {
+    let host = host.trim();
+    if host.starts_with('[') {
+        return match host.find(']') {
+            Some(end) => &host[..=end],
+            None => host,
+        };
+    }
+    match host.rsplit_once(':') {
+        // A bare IPv6 address has more than one colon and no port
+        Some((name, port)) if !name.contains(':') && port.chars().all(|c| c.is_ascii_digit()) => {
+            name
+        }
+        _ => host,
+    }
+}
+
+// Whether an `Origin` header is on the allow-list
+fn origin_allowed(allowed: &[String], origin: &str) -> bool {
+    let origin = normalize_origin(origin);
+    allowed
+        .iter()
+        .any(|allowed| allowed == ANY || normalize_origin(allowed) == origin)
+}
+
+// Origins compare without a trailing slash and regardless of case
+fn normalize_origin(origin: &str) -> String {
+    origin.trim().trim_end_matches('/').to_ascii_lowercase()
+}
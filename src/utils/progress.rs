@@ -0,0 +1,70 @@
+// Progress reporting for long-running operations
+
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+
+use crate::api::jsonrpc::JsonRpcNotification;
+use crate::api::mcp::ProgressParams;
+use crate::server::ServerEvent;
+use crate::utils::event_bus::EventBus;
+
+/// Reports the progress of a single operation
+///
+/// Every step is published as an `<operation>.progress` server event. When
+/// the request carried a `progressToken`, it is also sent to the requesting
+/// client as `notifications/progress`.
+#[derive(Clone)]
+pub struct ProgressReporter {
+    /// Name of the operation, such as "build" or "test"
+    operation: String,
+    /// Broadcast channel for events
+    event_tx: EventBus,
+    /// Progress token and channel of the requesting client
+    client: Option<(Value, mpsc::UnboundedSender<Value>)>,
+}
+
+impl ProgressReporter {
+    /// Create a reporter that only publishes server events
+    pub fn new(operation: impl Into<String>, event_tx: EventBus) -> Self {
+        Self {
+            operation: operation.into(),
+            event_tx,
+            client: None,
+        }
+    }
+
+    /// Also notify the client that asked for progress with the given token
+    pub fn with_client(mut self, token: Value, outbound: mpsc::UnboundedSender<Value>) -> Self {
+        self.client = Some((token, outbound));
+        self
+    }
+
+    /// Report that `progress` out of `total` units of work are done
+    pub fn report(&self, progress: u64, total: Option<u64>, message: impl Into<String>) {
+        let message = message.into();
+
+        if let Some((token, outbound)) = &self.client {
+            let params = ProgressParams {
+                progress_token: token.clone(),
+                progress: progress as f64,
+                total: total.map(|total| total as f64),
+                message: Some(message.clone()),
+            };
+            let _ = outbound.send(json!(JsonRpcNotification::new(
+                "notifications/progress",
+                json!(params),
+            )));
+        }
+
+        self.event_tx.send(ServerEvent::new(
+            format!("{}.progress", self.operation),
+            json!({
+                "operation": self.operation,
+                "progressToken": self.client.as_ref().map(|(token, _)| token),
+                "progress": progress,
+                "total": total,
+                "message": message,
+            }),
+        ));
+    }
+}
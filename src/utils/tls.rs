@@ -0,0 +1,129 @@
+// TLS certificates for serving HTTPS and WSS
+
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use anyhow::{anyhow, Context, Result};
+use log::info;
+use sha2::{Digest, Sha256};
+
+use crate::utils::file_utils;
+
+/// Certificate and private key the server presents to clients
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TlsConfig {
+    /// PEM file holding the certificate, followed by any intermediates
+    pub cert_path: PathBuf,
+    /// PEM file holding the private key
+    pub key_path: PathBuf,
+}
+
+impl TlsConfig {
+    /// Use a certificate and key from PEM files
+    pub fn new(cert_path: impl Into<PathBuf>, key_path: impl Into<PathBuf>) -> Self {
+        Self {
+            cert_path: cert_path.into(),
+            key_path: key_path.into(),
+        }
+    }
+
+    /// Use a self-signed certificate cached in `dir` for `names` (host names
+    /// and IP addresses)
+    ///
+    /// The certificate is generated the first time, and again whenever the
+    /// cached one was made for other names.
+    pub fn self_signed(dir: &Path, names: &[String]) -> Result<Self> {
+        let config = Self::new(dir.join("mcp-cert.pem"), dir.join("mcp-key.pem"));
+        let mut params = rcgen::CertificateParams::new(names.to_vec());
+        if config.has_names(&params.subject_alt_names) {
+            return Ok(config);
+        }
+        if config.cert_path.exists() {
+            info!(
+                "Regenerating the self-signed certificate for {}",
+                names.join(", ")
+            );
+        }
+
+        params
+            .distinguished_name
+            .push(rcgen::DnType::CommonName, "Orbit MCP server");
+        let cert = rcgen::Certificate::from_params(params)
+            .context("Failed to generate a self-signed certificate")?;
+
+        file_utils::write_private_file(
+            &config.key_path,
+            cert.serialize_private_key_pem().as_bytes(),
+        )
+        .with_context(|| format!("Failed to write {:?}", config.key_path))?;
+        let pem = cert
+            .serialize_pem()
+            .context("Failed to encode the self-signed certificate")?;
+        std::fs::write(&config.cert_path, pem)
+            .with_context(|| format!("Failed to write {:?}", config.cert_path))?;
+        Ok(config)
+    }
+
+    // Whether the cached certificate and key exist, and the certificate was
+    // made for exactly these names
+    fn has_names(&self, names: &[rcgen::SanType]) -> bool {
+        let (Ok(cert), Ok(key)) = (
+            std::fs::read_to_string(&self.cert_path),
+            std::fs::read_to_string(&self.key_path),
+        ) else {
+            return false;
+        };
+        let Ok(key_pair) = rcgen::KeyPair::from_pem(&key) else {
+            return false;
+        };
+        let Ok(cached) = rcgen::CertificateParams::from_ca_cert_pem(&cert, key_pair) else {
+            return false;
+        };
+
+        let cached: HashSet<_> = cached.subject_alt_names.iter().collect();
+        cached == names.iter().collect()
+    }
+
+    /// SHA-256 fingerprint of the certificate, for clients to pin
+    pub fn fingerprint(&self) -> Result<String> {
+        let pem = std::fs::read(&self.cert_path)
+            .with_context(|| format!("Failed to read {:?}", self.cert_path))?;
+        fingerprint(&pem).with_context(|| format!("Invalid certificate {:?}", self.cert_path))
+    }
+}
+
+/// Where the self-signed certificate is cached by default, `~/.orbit/tls`
+pub fn default_self_signed_dir() -> PathBuf {
+    file_utils::orbit_dir().join("tls")
+}
+
+/// SHA-256 fingerprint of the first certificate in a PEM file, as
+/// colon-separated hex pairs like `AB:CD:...`
+pub fn fingerprint(pem: &[u8]) -> Result<String> {
+    let certs = rustls_pemfile::certs(&mut &pem[..]).context("Failed to parse PEM")?;
+    let der = certs
+        .first()
+        .ok_or_else(|| anyhow!("No certificate found"))?;
+
+    Ok(der_fingerprint(der))
+}
+
+/// SHA-256 fingerprint of a DER-encoded certificate, in the same form
+pub fn der_fingerprint(der: &[u8]) -> String {
+    let digest = Sha256::digest(der);
+    let pairs: Vec<String> = digest.iter().map(|byte| format!("{:02X}", byte)).collect();
+    pairs.join(":")
+}
+
+/// Whether two fingerprints are the same, with or without colons and
+/// regardless of case
+pub fn same_fingerprint(a: &str, b: &str) -> bool {
+    let normalize = |fingerprint: &str| {
+        fingerprint
+            .chars()
+            .filter(|c| !c.is_whitespace() && *c != ':')
+            .map(|c| c.to_ascii_uppercase())
+            .collect::<String>()
+    };
+    normalize(a) == normalize(b)
+}
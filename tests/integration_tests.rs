@@ -2,7 +2,8 @@
 
 #[cfg(test)]
 mod tests {
-    use orbit_mcp::server::McpServer;
+    use futures_util::{SinkExt, StreamExt};
+    use orbit_mcp::server::{McpServer, ServerOptions};
     use serde_json::json;
     use std::path::Path;
     use std::time::Duration;
@@ -12,23 +13,68 @@ mod tests {
 
     // Start the server on a dynamic port for testing
     async fn start_test_server() -> (u16, tokio::task::JoinHandle<()>) {
-        // Choose a port that's likely to be free
-        let port = 43210;
-        let server = McpServer::new("127.0.0.1".to_string(), port, Path::new("."))
+        start_test_server_with(ServerOptions::default()).await
+    }
+
+    // Start the server with custom options
+    async fn start_test_server_with(options: ServerOptions) -> (u16, tokio::task::JoinHandle<()>) {
+        start_test_server_in(Path::new("."), options).await
+    }
+
+    // Start the server for another project directory
+    async fn start_test_server_in(
+        project_dir: &Path,
+        options: ServerOptions,
+    ) -> (u16, tokio::task::JoinHandle<()>) {
+        // Let the operating system pick a free port, so tests can run in parallel
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let server = McpServer::with_options("127.0.0.1".to_string(), port, project_dir, options)
             .await
             .unwrap();
 
-        // Start the server in a background task
+        // Start the server in a background task; the listener is already
+        // bound, so connections made before it starts serving just wait
         let handle = tokio::spawn(async move {
-            server.run().await.unwrap();
+            server.serve(listener).await.unwrap();
         });
 
-        // Give the server time to start
-        tokio::time::sleep(Duration::from_millis(100)).await;
-
         (port, handle)
     }
 
+    // Read the next frame from the server as JSON
+    async fn next_json<S>(
+        ws_stream: &mut S,
+    ) -> Result<serde_json::Value, Box<dyn std::error::Error>>
+    where
+        S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        let msg = timeout(Duration::from_secs(1), ws_stream.next())
+            .await?
+            .ok_or("connection closed")??;
+        Ok(serde_json::from_str(msg.to_text()?)?)
+    }
+
+    // Read a reply and a notification cancelling a server request, in
+    // whichever order they come
+    async fn reply_and_cancellation<S>(
+        ws_stream: &mut S,
+    ) -> Result<(serde_json::Value, serde_json::Value), Box<dyn std::error::Error>>
+    where
+        S: StreamExt<Item = Result<Message, tokio_tungstenite::tungstenite::Error>> + Unpin,
+    {
+        let (mut reply, mut cancelled) = (None, None);
+        while reply.is_none() || cancelled.is_none() {
+            let json = next_json(ws_stream).await?;
+            if json["method"] == "notifications/cancelled" {
+                cancelled = Some(json);
+            } else {
+                reply = Some(json);
+            }
+        }
+        Ok((reply.unwrap(), cancelled.unwrap()))
+    }
+
     #[tokio::test]
     async fn test_server_connection() -> Result<(), Box<dyn std::error::Error>> {
         // Start the server
@@ -39,8 +85,10 @@ mod tests {
         let (mut ws_stream, _) = connect_async(url).await?;
 
         // Wait for the welcome message
-        let msg = timeout(Duration::from_secs(1), ws_stream.next()).await??;
-        if let Message::Text(text) = msg.unwrap() {
+        let msg = timeout(Duration::from_secs(1), ws_stream.next())
+            .await?
+            .ok_or("connection closed")?;
+        if let Message::Text(text) = msg? {
             let json: serde_json::Value = serde_json::from_str(&text)?;
             assert_eq!(json["method"], "server.welcome");
         } else {
@@ -55,17 +103,2697 @@ mod tests {
     }
 
     #[tokio::test]
-    async fn test_status_endpoint() -> Result<(), Box<dyn std::error::Error>> {
+    async fn test_jsonrpc_dispatch() -> Result<(), Box<dyn std::error::Error>> {
         // Start the server
         let (port, handle) = start_test_server().await;
 
-        // Call the status endpoint
-        let response = reqwest::get(format!("http://127.0.0.1:{}/api/status", port)).await?;
+        // Connect to the server and skip the welcome message
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
 
-        assert_eq!(response.status(), 200);
+        // A known method gets a result carrying the request ID
+        let request = json!({ "jsonrpc": "2.0", "id": 7, "method": "component.list" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
 
-        let json: serde_json::Value = response.json().await?;
-        assert_eq!(json["status"], "running");
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 7);
+        assert!(json["result"]["components"].is_array());
+
+        // An unknown method gets a method-not-found error
+        let request = json!({ "jsonrpc": "2.0", "id": "x", "method": "component.frobnicate" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], "x");
+        assert_eq!(json["error"]["code"], -32601);
+
+        // Malformed JSON gets a parse error
+        ws_stream
+            .send(Message::Text("{not json".to_string()))
+            .await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], serde_json::Value::Null);
+        assert_eq!(json["error"]["code"], -32700);
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_mcp_lifecycle() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        // Connect to the server and skip the welcome message
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        // Initialize with a supported protocol version
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-03-26",
+                "capabilities": {},
+                "clientInfo": { "name": "test-client", "version": "1.0.0" }
+            }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["result"]["protocolVersion"], "2025-03-26");
+        assert_eq!(json["result"]["serverInfo"]["name"], "orbit-mcp");
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        ws_stream
+            .send(Message::Text(notification.to_string()))
+            .await?;
+
+        // List the tools
+        let request = json!({ "jsonrpc": "2.0", "id": 2, "method": "tools/list" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        let tools = json["result"]["tools"].as_array().unwrap();
+        assert!(tools.iter().any(|tool| tool["name"] == "component_list"));
+        assert!(tools
+            .iter()
+            .all(|tool| tool["inputSchema"]["type"] == "object"));
+
+        // Call a tool
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "tools/call",
+            "params": { "name": "component_get", "arguments": { "id": "counter" } }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 3);
+        assert_eq!(json["result"]["isError"], false);
+        assert_eq!(
+            json["result"]["structuredContent"]["component"]["id"],
+            "counter"
+        );
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_resources() -> Result<(), Box<dyn std::error::Error>> {
+        // A scratch file inside the project that the test can modify
+        let scratch = Path::new(env!("CARGO_TARGET_TMPDIR")).join("resource-test.md");
+        tokio::fs::write(&scratch, "# Before\n").await?;
+        let relative = scratch.strip_prefix(std::env::current_dir()?)?;
+        let uri = format!("orbit://file/{}", relative.to_string_lossy());
+
+        // Start the server, allowing `target` where the scratch file lives
+        let (port, handle) = start_test_server_with(ServerOptions {
+            denied_paths: vec![".git".to_string()],
+            ..ServerOptions::default()
+        })
+        .await;
+
+        // Connect to the server and skip the welcome message
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        // List the resources
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "resources/list" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        let resources = json["result"]["resources"].as_array().unwrap();
+        assert!(resources
+            .iter()
+            .any(|resource| resource["uri"] == "orbit://file/Cargo.toml"));
+
+        // Read a resource
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "resources/read",
+            "params": { "uri": uri }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["result"]["contents"][0]["text"], "# Before\n");
+        assert_eq!(json["result"]["contents"][0]["mimeType"], "text/markdown");
+
+        // Paths outside the project are rejected
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "resources/read",
+            "params": { "uri": "orbit://file/../Cargo.toml" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["error"]["code"], -32602);
+
+        // Subscribe and change the file
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "resources/subscribe",
+            "params": { "uri": uri }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 4);
+
+        tokio::fs::write(&scratch, "# After\n").await?;
+
+        // The update notification arrives alongside the file.changed event
+        let mut updated = false;
+        for _ in 0..4 {
+            let json = next_json(&mut ws_stream).await?;
+            if json["method"] == "notifications/resources/updated" {
+                assert_eq!(json["params"]["uri"], uri.as_str());
+                updated = true;
+                break;
+            }
+        }
+        assert!(updated);
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+        tokio::fs::remove_file(&scratch).await?;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_prompts() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        // Connect to the server and skip the welcome message
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        // List the prompts
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "prompts/list" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        let prompts = json["result"]["prompts"].as_array().unwrap();
+        let prompt = prompts
+            .iter()
+            .find(|prompt| prompt["name"] == "create_component")
+            .ok_or("create_component prompt missing")?;
+        assert_eq!(prompt["arguments"][0]["name"], "name");
+        assert_eq!(prompt["arguments"][0]["required"], true);
+
+        // Render a prompt
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "prompts/get",
+            "params": { "name": "create_component", "arguments": { "name": "TodoList" } }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        let message = &json["result"]["messages"][0];
+        assert_eq!(message["role"], "user");
+        assert!(message["content"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("`TodoList`"));
+
+        // Prompts are filled with the latest build results
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "prompts/get",
+            "params": { "name": "fix_build_errors" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert!(json["result"]["messages"][0]["content"]["text"]
+            .as_str()
+            .unwrap()
+            .contains("No build has been run yet."));
+
+        // Required arguments are enforced
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "prompts/get",
+            "params": { "name": "create_component" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["error"]["code"], -32602);
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_progress_notifications() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        // Connect to the server and skip the welcome message
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        // Run the tests and ask for progress
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": {
+                "name": "test_run",
+                "arguments": {
+                    "options": {
+                        "filter": null,
+                        "coverage": false,
+                        "verbose": false,
+                        "no_fail_fast": false
+                    }
+                },
+                "_meta": { "progressToken": "tests-1" }
+            }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        // Progress arrives before the response, both directly and as server events
+        let mut progress = Vec::new();
+        let mut events = 0;
+        loop {
+            let json = next_json(&mut ws_stream).await?;
+            if json["id"] == 1 {
+                assert_eq!(json["result"]["isError"], false);
+                break;
+            }
+            if json["method"] == "notifications/progress" {
+                assert_eq!(json["params"]["progressToken"], "tests-1");
+                assert_eq!(json["params"]["total"], 10.0);
+                progress.push(json["params"]["progress"].as_f64().unwrap());
+            } else if json["params"]["type"] == "test.progress" {
+                events += 1;
+            }
+        }
+
+        assert_eq!(progress.first(), Some(&0.0));
+        assert_eq!(progress.last(), Some(&10.0));
+        assert!(progress.windows(2).all(|pair| pair[0] < pair[1]));
+
+        // Broadcast events may still be in flight when the response arrives
+        while events < progress.len() {
+            let json = next_json(&mut ws_stream).await?;
+            if json["params"]["type"] == "test.progress" {
+                assert_eq!(json["params"]["payload"]["operation"], "test");
+                events += 1;
+            }
+        }
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_cancellation() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        // Connect to the server and skip the welcome message
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        // Cancelling requests that already finished is silently ignored
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/cancelled",
+            "params": { "requestId": 99, "reason": "no longer needed" }
+        });
+        ws_stream
+            .send(Message::Text(notification.to_string()))
+            .await?;
+
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "$/cancelRequest",
+            "params": { "id": "gone" }
+        });
+        ws_stream
+            .send(Message::Text(notification.to_string()))
+            .await?;
+
+        // The next frame is the reply to the following request
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 1);
+        assert_eq!(json["result"], json!({}));
+
+        // A request still waiting on the client's model can be cancelled
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": { "sampling": {} },
+                "clientInfo": { "name": "sampler", "version": "1.0.0" }
+            }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        next_json(&mut ws_stream).await?;
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "component.describe",
+            "params": { "id": "counter" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let sampling = next_json(&mut ws_stream).await?;
+        assert_eq!(sampling["method"], "sampling/createMessage");
+
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/cancelled",
+            "params": { "requestId": 3 }
+        });
+        ws_stream
+            .send(Message::Text(notification.to_string()))
+            .await?;
+
+        // ...and so is the sampling it was waiting on
+        let (json, cancelled) = reply_and_cancellation(&mut ws_stream).await?;
+        assert_eq!(json["id"], 3);
+        assert_eq!(json["error"]["code"], -32800);
+        assert_eq!(cancelled["params"]["requestId"], sampling["id"]);
+
+        // A late answer from the client goes nowhere
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": sampling["id"],
+            "result": {
+                "role": "assistant",
+                "content": { "type": "text", "text": "Too late." },
+                "model": "test-model"
+            }
+        });
+        ws_stream.send(Message::Text(response.to_string())).await?;
+
+        let request = json!({ "jsonrpc": "2.0", "id": 4, "method": "ping" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 4);
+
+        // The same goes for tool calls
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "tools/call",
+            "params": { "name": "component_describe", "arguments": { "id": "counter" } }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let sampling = next_json(&mut ws_stream).await?;
+        assert_eq!(sampling["method"], "sampling/createMessage");
+
+        let notification = json!({
+            "jsonrpc": "2.0",
+            "method": "notifications/cancelled",
+            "params": { "requestId": 5 }
+        });
+        ws_stream
+            .send(Message::Text(notification.to_string()))
+            .await?;
+
+        let (json, cancelled) = reply_and_cancellation(&mut ws_stream).await?;
+        assert_eq!(json["id"], 5);
+        assert_eq!(json["error"]["code"], -32800);
+        assert_eq!(cancelled["params"]["requestId"], sampling["id"]);
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_jsonrpc_batch() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        // Connect to the server and skip the welcome message
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        // Requests get one entry each, notifications none
+        let batch = json!([
+            { "jsonrpc": "2.0", "id": 1, "method": "component.list" },
+            { "jsonrpc": "2.0", "method": "notifications/initialized" },
+            { "jsonrpc": "2.0", "id": 2, "method": "project.info" },
+            { "jsonrpc": "2.0", "id": 3, "method": "no.such.method" },
+            1
+        ]);
+        ws_stream.send(Message::Text(batch.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        let responses = json.as_array().ok_or("expected a batch response")?;
+        assert_eq!(responses.len(), 4);
+
+        let response = |id: serde_json::Value| {
+            responses
+                .iter()
+                .find(|response| response["id"] == id)
+                .cloned()
+                .unwrap_or_default()
+        };
+        assert!(response(json!(1))["result"]["components"].is_array());
+        assert!(response(json!(2))["result"].is_object());
+        assert_eq!(response(json!(3))["error"]["code"], -32601);
+        assert_eq!(response(json!(null))["error"]["code"], -32600);
+
+        // An empty batch is a single invalid request
+        ws_stream.send(Message::Text("[]".to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["error"]["code"], -32600);
+
+        // A batch of notifications gets no reply at all
+        let batch = json!([{ "jsonrpc": "2.0", "method": "notifications/initialized" }]);
+        ws_stream.send(Message::Text(batch.to_string())).await?;
+
+        let request = json!({ "jsonrpc": "2.0", "id": 4, "method": "ping" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 4);
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_event_subscriptions() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        // Connect to the server and skip the welcome message
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        // Only ask for test events
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "events.subscribe",
+            "params": { "events": ["test.*"] }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        let subscription_id = json["result"]["subscriptionId"]
+            .as_str()
+            .ok_or("missing subscription ID")?
+            .to_string();
+
+        // Run a build, then the tests
+        let options = json!({
+            "target": "web",
+            "mode": "development",
+            "optimize": false,
+            "source_maps": false,
+            "features": []
+        });
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "build.compile",
+            "params": { "options": options }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 2);
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "test.run",
+            "params": {
+                "options": {
+                    "filter": null,
+                    "coverage": false,
+                    "verbose": false,
+                    "no_fail_fast": false
+                }
+            }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        // Build events come before test events, so any leak would show up here
+        let mut test_events = 0;
+        let mut answered = false;
+        while test_events < 11 || !answered {
+            let json = next_json(&mut ws_stream).await?;
+            if json["id"] == 3 {
+                answered = true;
+            } else {
+                assert_eq!(json["params"]["type"], "test.progress");
+                test_events += 1;
+            }
+        }
+
+        // Invalid patterns are rejected
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "events.subscribe",
+            "params": { "events": ["build.[*"] }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["error"]["code"], -32602);
+
+        // Unsubscribing by ID
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "events.unsubscribe",
+            "params": { "subscriptionId": subscription_id }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["result"], json!({}));
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_event_replay() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        // Connect to the server and skip the welcome message
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        // Run a build and note the IDs of its events
+        let options = json!({
+            "target": "web",
+            "mode": "development",
+            "optimize": false,
+            "source_maps": false,
+            "features": []
+        });
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "build.compile",
+            "params": { "options": options }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        // Four build phases plus the final step
+        let mut event_ids = Vec::new();
+        let mut answered = false;
+        while event_ids.len() < 5 || !answered {
+            let json = next_json(&mut ws_stream).await?;
+            if json["id"] == 1 {
+                answered = true;
+            } else {
+                assert_eq!(json["method"], "server.event");
+                event_ids.push(json["params"]["id"].as_u64().ok_or("missing event ID")?);
+            }
+        }
+        assert!(event_ids.windows(2).all(|pair| pair[1] == pair[0] + 1));
+        ws_stream.close(None).await?;
+
+        // Reconnect after the first event and get the rest replayed in order
+        let url = Url::parse(&format!(
+            "ws://127.0.0.1:{}/api/ws?lastEventId={}",
+            port, event_ids[0]
+        ))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["method"], "server.welcome");
+        assert_eq!(json["params"]["lastEventId"], *event_ids.last().unwrap());
+
+        for expected in &event_ids[1..] {
+            let json = next_json(&mut ws_stream).await?;
+            assert_eq!(json["params"]["id"], *expected);
+        }
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_session_resume() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        // Connect and note the session from the welcome message
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["params"]["resumed"], false);
+        let client_id = json["params"]["clientId"].clone();
+        let resume_token = json["params"]["resumeToken"]
+            .as_str()
+            .ok_or("missing resume token")?
+            .to_string();
+
+        // Set up some session state, then drop the connection
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "events.subscribe",
+            "params": { "events": ["test.*"] }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        let subscription_id = json["result"]["subscriptionId"].clone();
+        drop(ws_stream);
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Resuming reclaims the same client and its subscriptions
+        let url = Url::parse(&format!(
+            "ws://127.0.0.1:{}/api/ws?resumeToken={}",
+            port, resume_token
+        ))?;
+        let (mut ws_stream, _) = connect_async(url.clone()).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["params"]["resumed"], true);
+        assert_eq!(json["params"]["clientId"], client_id);
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "events.unsubscribe",
+            "params": { "subscriptionId": subscription_id }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 2);
+        assert!(json["error"].is_null());
+
+        // A session can't be resumed while it's still connected
+        let (mut other_stream, _) = connect_async(url).await?;
+        let json = next_json(&mut other_stream).await?;
+        assert_eq!(json["params"]["resumed"], false);
+        assert_ne!(json["params"]["clientId"], client_id);
+
+        // Clean up
+        other_stream.close(None).await?;
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_keepalive() -> Result<(), Box<dyn std::error::Error>> {
+        // Start a server with short keepalive timings and no session grace
+        let (port, handle) = start_test_server_with(ServerOptions {
+            session_grace: Duration::ZERO,
+            ping_interval: Duration::from_millis(100),
+            pong_timeout: Duration::from_millis(200),
+            idle_timeout: Duration::from_millis(600),
+            ..ServerOptions::default()
+        })
+        .await;
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let status_url = format!("http://127.0.0.1:{}/api/status", port);
+
+        // A client that never reads never answers pings
+        let (ws_stream, _) = connect_async(url.clone()).await?;
+        tokio::time::sleep(Duration::from_millis(500)).await;
+
+        let json: serde_json::Value = reqwest::get(&status_url).await?.json().await?;
+        assert_eq!(json["active_connections"], 0);
+        assert_eq!(
+            json["recent_clients"][0]["history"][0]["reason"],
+            "pong_timeout"
+        );
+        drop(ws_stream);
+
+        // A client that answers pings but sends nothing is closed as idle
+        let (mut ws_stream, _) = connect_async(url).await?;
+        let close = timeout(Duration::from_secs(2), async {
+            while let Some(Ok(msg)) = ws_stream.next().await {
+                if let Message::Close(frame) = msg {
+                    return frame;
+                }
+            }
+            None
+        })
+        .await?
+        .ok_or("no close frame")?;
+        assert_eq!(close.reason, "idle for too long");
+
+        let json: serde_json::Value = reqwest::get(&status_url).await?.json().await?;
+        assert_eq!(
+            json["recent_clients"][1]["history"][0]["reason"],
+            "idle_timeout"
+        );
+
+        // Clean up
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_client_identification() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+        let status_url = format!("http://127.0.0.1:{}/api/status", port);
+
+        // Connect an MCP client and a plain WebSocket client
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut mcp_stream, _) = connect_async(url.clone()).await?;
+        next_json(&mut mcp_stream).await?;
+        let (mut hello_stream, _) = connect_async(url).await?;
+        next_json(&mut hello_stream).await?;
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": { "roots": { "listChanged": true }, "sampling": {} },
+                "clientInfo": { "name": "mcp-client", "version": "2.0.0" }
+            }
+        });
+        mcp_stream.send(Message::Text(request.to_string())).await?;
+        next_json(&mut mcp_stream).await?;
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "client.hello",
+            "params": { "name": "dashboard", "version": "0.3.0", "capabilities": ["events"] }
+        });
+        hello_stream
+            .send(Message::Text(request.to_string()))
+            .await?;
+
+        let json = next_json(&mut hello_stream).await?;
+        assert!(json["result"]["clientId"].is_string());
+        assert_eq!(json["result"]["serverInfo"]["name"], "orbit-mcp");
+
+        // Both clients show up by name in the status
+        let status: serde_json::Value = reqwest::get(&status_url).await?.json().await?;
+        let clients = status["clients"].as_array().unwrap();
+
+        let mcp_client = clients
+            .iter()
+            .find(|client| client["name"] == "mcp-client")
+            .ok_or("MCP client missing")?;
+        assert_eq!(mcp_client["version"], "2.0.0");
+        assert_eq!(mcp_client["protocol_version"], "2025-06-18");
+        let capabilities = mcp_client["capabilities"].as_array().unwrap();
+        assert!(capabilities.contains(&json!("roots.listChanged")));
+        assert!(capabilities.contains(&json!("sampling")));
+
+        let dashboard = clients
+            .iter()
+            .find(|client| client["name"] == "dashboard")
+            .ok_or("dashboard missing")?;
+        assert_eq!(dashboard["capabilities"], json!(["events"]));
+
+        // Server events only go to the client that declared it handles them
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "build.compile",
+            "params": {
+                "options": {
+                    "target": "web",
+                    "mode": "development",
+                    "optimize": false,
+                    "source_maps": false,
+                    "features": []
+                }
+            }
+        });
+        mcp_stream.send(Message::Text(request.to_string())).await?;
+
+        let json = next_json(&mut hello_stream).await?;
+        assert_eq!(json["params"]["type"], "build.progress");
+
+        let json = next_json(&mut mcp_stream).await?;
+        assert_eq!(json["id"], 2);
+        assert!(timeout(Duration::from_millis(200), mcp_stream.next())
+            .await
+            .is_err());
+
+        // Clean up
+        mcp_stream.close(None).await?;
+        hello_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_logging() -> Result<(), Box<dyn std::error::Error>> {
+        // Forward log records; the terminal only gets errors
+        let _ = orbit_mcp::utils::logging::init(env_logger::Builder::new());
+
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        // Ask for info messages and above
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "logging/setLevel",
+            "params": { "level": "info" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["result"], json!({}));
+
+        // Identifying logs a message while handling request 2
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "client.hello",
+            "params": { "name": "log-reader", "version": "1.0.0" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let mut message = None;
+        while message.is_none() {
+            let json = next_json(&mut ws_stream).await?;
+            if json["method"] == "notifications/message" && json["params"]["data"]["requestId"] == 2
+            {
+                message = Some(json);
+            }
+        }
+        let message = message.unwrap();
+        assert_eq!(message["params"]["level"], "info");
+        assert_eq!(message["params"]["logger"], "orbit_mcp::handlers::clients");
+        assert!(message["params"]["data"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("log-reader"));
+
+        // Unknown levels are rejected
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "logging/setLevel",
+            "params": { "level": "verbose" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        loop {
+            let json = next_json(&mut ws_stream).await?;
+            if json["id"] == 3 {
+                assert_eq!(json["error"]["code"], -32602);
+                break;
+            }
+        }
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        // Clients only get records about their own messages
+        let (port, handle) = start_test_server_with(ServerOptions {
+            auth_token: Some("admin-token".to_string()),
+            scoped_tokens: [(
+                "reader-token".to_string(),
+                "read".parse::<orbit_mcp::api::auth::Scopes>()?,
+            )]
+            .into(),
+            ..ServerOptions::default()
+        })
+        .await;
+        let url = format!("ws://127.0.0.1:{}/api/ws", port);
+        let mut streams = Vec::new();
+        for token in ["reader-token", "admin-token"] {
+            let (mut stream, _) = connect_async(format!("{}?access_token={}", url, token)).await?;
+            next_json(&mut stream).await?;
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "logging/setLevel",
+                "params": { "level": "debug" }
+            });
+            stream.send(Message::Text(request.to_string())).await?;
+            while next_json(&mut stream).await?["id"] != 1 {}
+            streams.push(stream);
+        }
+        let (mut reader, mut writer) = (streams.remove(0), streams.remove(0));
+
+        // The writer sees its own raw message logged...
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "component.get",
+            "params": { "id": "secret-marker" }
+        });
+        writer.send(Message::Text(request.to_string())).await?;
+        // Records are forwarded separately, so they may come after the reply
+        loop {
+            let json = next_json(&mut writer).await?;
+            if json["method"] == "notifications/message"
+                && json.to_string().contains("secret-marker")
+            {
+                break;
+            }
+        }
+
+        // ...but another client never does, whatever its level
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let request = json!({ "jsonrpc": "2.0", "id": 3, "method": "component.list" });
+        reader.send(Message::Text(request.to_string())).await?;
+        loop {
+            let json = next_json(&mut reader).await?;
+            assert!(!json.to_string().contains("secret-marker"), "{}", json);
+            if json["id"] == 3 {
+                break;
+            }
+        }
+
+        // Clean up
+        reader.close(None).await?;
+        writer.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_roots() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server, letting clients open projects under /workspace
+        let (port, handle) = start_test_server_with(ServerOptions {
+            allowed_roots: vec!["/workspace".into()],
+            ..ServerOptions::default()
+        })
+        .await;
+
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": { "roots": { "listChanged": true } },
+                "clientInfo": { "name": "editor", "version": "1.0.0" }
+            }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        next_json(&mut ws_stream).await?;
+
+        // Once initialized, the server asks for the client's roots
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        ws_stream
+            .send(Message::Text(notification.to_string()))
+            .await?;
+
+        let request = next_json(&mut ws_stream).await?;
+        assert_eq!(request["method"], "roots/list");
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": {
+                "roots": [
+                    { "uri": "file:///workspace/app", "name": "app" },
+                    { "uri": "file:///workspace/shared%20ui" },
+                    { "uri": "file:///", "name": "everything" },
+                    { "uri": "file:///etc", "name": "etc" }
+                ]
+            }
+        });
+        ws_stream.send(Message::Text(response.to_string())).await?;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Each root is a project, unless it lies outside the allowed directories
+        let request = json!({ "jsonrpc": "2.0", "id": 2, "method": "project.list" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        let projects = json["result"]["projects"].as_array().unwrap();
+        assert_eq!(projects.len(), 2);
+        assert_eq!(projects[0]["name"], "app");
+        assert_eq!(projects[1]["name"], "shared ui");
+        assert_eq!(projects[1]["path"], "/workspace/shared ui");
+
+        // Requests address a project by name, and default to the first root
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "component.get",
+            "params": { "id": "counter", "project": "shared ui" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(
+            json["result"]["component"]["path"],
+            "/workspace/shared ui/src/counter.orbit"
+        );
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 4,
+            "method": "component.get",
+            "params": { "id": "counter" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(
+            json["result"]["component"]["path"],
+            "/workspace/app/src/counter.orbit"
+        );
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 5,
+            "method": "component.get",
+            "params": { "id": "counter", "project": "elsewhere" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["error"]["code"], -32001);
+
+        // Changed roots are fetched again
+        let notification =
+            json!({ "jsonrpc": "2.0", "method": "notifications/roots/list_changed" });
+        ws_stream
+            .send(Message::Text(notification.to_string()))
+            .await?;
+
+        let request = next_json(&mut ws_stream).await?;
+        assert_eq!(request["method"], "roots/list");
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": { "roots": [{ "uri": "file:///workspace/other", "name": "other" }] }
+        });
+        ws_stream.send(Message::Text(response.to_string())).await?;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let request = json!({ "jsonrpc": "2.0", "id": 6, "method": "project.list" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(
+            json["result"]["projects"],
+            json!([{ "name": "other", "path": "/workspace/other", "uri": "file:///workspace/other" }])
+        );
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_root_subscriptions() -> Result<(), Box<dyn std::error::Error>> {
+        // A scratch project holding two roots with the same component
+        let scratch = Path::new(env!("CARGO_TARGET_TMPDIR")).join("root-subscriptions");
+        for root in ["a", "b"] {
+            tokio::fs::create_dir_all(scratch.join(root).join("src")).await?;
+            tokio::fs::write(
+                scratch.join(root).join("src/counter.orbit"),
+                "<template />\n",
+            )
+            .await?;
+        }
+        let scratch = scratch.canonicalize()?;
+
+        // Start the server
+        let (port, handle) = start_test_server_in(&scratch, ServerOptions::default()).await;
+
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": { "roots": {} },
+                "clientInfo": { "name": "editor", "version": "1.0.0" }
+            }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        next_json(&mut ws_stream).await?;
+
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        ws_stream
+            .send(Message::Text(notification.to_string()))
+            .await?;
+
+        let request = next_json(&mut ws_stream).await?;
+        assert_eq!(request["method"], "roots/list");
+        let roots: Vec<_> = ["a", "b"]
+            .iter()
+            .map(|root| {
+                json!({
+                    "uri": format!("file://{}", scratch.join(root).display()),
+                    "name": root
+                })
+            })
+            .collect();
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": { "roots": roots }
+        });
+        ws_stream.send(Message::Text(response.to_string())).await?;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // Subscribe to the same resource in both roots
+        for (id, root) in [(2, "a"), (3, "b")] {
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "resources/subscribe",
+                "params": { "uri": "orbit://component/counter", "project": root }
+            });
+            ws_stream.send(Message::Text(request.to_string())).await?;
+            let json = next_json(&mut ws_stream).await?;
+            assert_eq!(json["id"], id);
+            assert!(json.get("error").is_none());
+        }
+
+        // Each change is reported for the root it happened in
+        for root in ["b", "a"] {
+            tokio::fs::write(
+                scratch.join(root).join("src/counter.orbit"),
+                "<template>changed</template>\n",
+            )
+            .await?;
+
+            let mut updated = None;
+            for _ in 0..4 {
+                let json = next_json(&mut ws_stream).await?;
+                if json["method"] == "notifications/resources/updated" {
+                    updated = Some(json);
+                    break;
+                }
+            }
+            let json = updated.expect("no update notification");
+            assert_eq!(json["params"]["uri"], "orbit://component/counter");
+            assert_eq!(
+                json["params"]["project"],
+                scratch.join(root).to_string_lossy().as_ref()
+            );
+        }
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_completion() -> Result<(), Box<dyn std::error::Error>> {
+        // A scratch project with components, tests and features
+        let project = Path::new(env!("CARGO_TARGET_TMPDIR")).join("completion-project");
+        tokio::fs::create_dir_all(project.join("src")).await?;
+        tokio::fs::create_dir_all(project.join("tests")).await?;
+        for id in ["counter", "user-profile", "todo-list"] {
+            tokio::fs::write(project.join(format!("src/{}.orbit", id)), "<template />\n").await?;
+        }
+        tokio::fs::write(
+            project.join("Cargo.toml"),
+            "[package]\nname = \"app\"\n\n[features]\nssr = []\nhydrate = []\ndev-tools = []\n",
+        )
+        .await?;
+        tokio::fs::write(
+            project.join("tests/app.rs"),
+            "#[test]\nfn renders_counter() {}\n\n#[tokio::test]\nasync fn loads_user_profile() {}\n",
+        )
+        .await?;
+
+        // Start the server
+        let (port, handle) = start_test_server_in(&project, ServerOptions::default()).await;
+
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        let cases = [
+            (
+                json!({ "type": "ref/tool", "name": "component_get" }),
+                "id",
+                "us",
+                "user-profile",
+            ),
+            (
+                json!({ "type": "ref/tool", "name": "component_get" }),
+                "id",
+                "tdl",
+                "todo-list",
+            ),
+            (
+                json!({ "type": "ref/prompt", "name": "write_tests" }),
+                "component",
+                "cou",
+                "counter",
+            ),
+            (
+                json!({ "type": "ref/resource", "uri": "orbit://component/{id}" }),
+                "id",
+                "",
+                "counter",
+            ),
+            (
+                json!({ "type": "ref/tool", "name": "test_run" }),
+                "options.filter",
+                "load",
+                "loads_user_profile",
+            ),
+            (
+                json!({ "type": "ref/tool", "name": "build_compile" }),
+                "options.features",
+                "h",
+                "hydrate",
+            ),
+            (
+                json!({ "type": "ref/tool", "name": "component_create" }),
+                "template",
+                "fo",
+                "form",
+            ),
+            (
+                json!({ "type": "ref/tool", "name": "docs_query" }),
+                "category",
+                "tu",
+                "tutorial",
+            ),
+        ];
+
+        for (id, (reference, argument, value, expected)) in cases.into_iter().enumerate() {
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "completion/complete",
+                "params": { "ref": reference, "argument": { "name": argument, "value": value } }
+            });
+            ws_stream.send(Message::Text(request.to_string())).await?;
+
+            let json = next_json(&mut ws_stream).await?;
+            assert_eq!(
+                json["result"]["completion"]["values"][0], expected,
+                "completing {} = {:?}",
+                argument, value
+            );
+        }
+
+        // Prefix matches rank above fuzzy ones
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 100,
+            "method": "completion/complete",
+            "params": {
+                "ref": { "type": "ref/tool", "name": "component_get" },
+                "argument": { "name": "id", "value": "" }
+            }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["result"]["completion"]["total"], 3);
+        assert_eq!(json["result"]["completion"]["hasMore"], false);
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_sampling() -> Result<(), Box<dyn std::error::Error>> {
+        use std::process::Stdio;
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url.clone()).await?;
+        next_json(&mut ws_stream).await?;
+
+        // Clients without the sampling capability aren't asked
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "component.describe",
+            "params": { "id": "counter" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 1);
+        assert!(json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("sampling/createMessage"));
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": { "sampling": {} },
+                "clientInfo": { "name": "sampler", "version": "1.0.0" }
+            }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 2);
+
+        // The server asks the client's model while handling the request
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": 3,
+            "method": "component.describe",
+            "params": { "id": "counter" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+
+        let sampling = next_json(&mut ws_stream).await?;
+        assert_eq!(sampling["method"], "sampling/createMessage");
+        assert!(sampling["params"]["maxTokens"].is_number());
+        assert_eq!(sampling["params"]["messages"][0]["role"], "user");
+
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": sampling["id"],
+            "result": {
+                "role": "assistant",
+                "content": { "type": "text", "text": "A button that counts clicks." },
+                "model": "test-model",
+                "stopReason": "endTurn"
+            }
+        });
+        ws_stream.send(Message::Text(response.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 3);
+        assert_eq!(
+            json["result"]["description"],
+            "A button that counts clicks."
+        );
+
+        // Errors from the client fail the request
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let sampling = next_json(&mut ws_stream).await?;
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": sampling["id"],
+            "error": { "code": -1, "message": "User rejected sampling request" }
+        });
+        ws_stream.send(Message::Text(response.to_string())).await?;
+
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["id"], 3);
+        assert!(json["error"]["message"]
+            .as_str()
+            .unwrap()
+            .contains("User rejected"));
+
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        // The same round trip works over stdio
+        let mut child = tokio::process::Command::new(env!("CARGO_BIN_EXE_orbit-mcp"))
+            .args(["--transport", "stdio", "--project", "."])
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+        let mut stdin = child.stdin.take().unwrap();
+        let mut stdout = BufReader::new(child.stdout.take().unwrap()).lines();
+
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": { "sampling": {} },
+                "clientInfo": { "name": "sampler", "version": "1.0.0" }
+            }
+        });
+        stdin
+            .write_all(format!("{}\n", initialize).as_bytes())
+            .await?;
+        timeout(Duration::from_secs(5), stdout.next_line()).await??;
+
+        stdin.write_all(format!("{}\n", request).as_bytes()).await?;
+        let line = timeout(Duration::from_secs(5), stdout.next_line())
+            .await??
+            .ok_or("stdout closed")?;
+        let sampling: serde_json::Value = serde_json::from_str(&line)?;
+        assert_eq!(sampling["method"], "sampling/createMessage");
+
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": sampling["id"],
+            "result": {
+                "role": "assistant",
+                "content": { "type": "text", "text": "Counts clicks." },
+                "model": "test-model"
+            }
+        });
+        stdin
+            .write_all(format!("{}\n", response).as_bytes())
+            .await?;
+
+        let line = timeout(Duration::from_secs(5), stdout.next_line())
+            .await??
+            .ok_or("stdout closed")?;
+        let json: serde_json::Value = serde_json::from_str(&line)?;
+        assert_eq!(json["id"], 3);
+        assert_eq!(json["result"]["description"], "Counts clicks.");
+
+        // Clean up
+        drop(stdin);
+        timeout(Duration::from_secs(5), child.wait()).await??;
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_stdio_transport() -> Result<(), Box<dyn std::error::Error>> {
+        use std::process::Stdio;
+        use tokio::io::{AsyncBufReadExt, AsyncWriteExt, BufReader};
+
+        // Launch the server as an MCP host would
+        let mut child = tokio::process::Command::new(env!("CARGO_BIN_EXE_orbit-mcp"))
+            .args(["--transport", "stdio", "--project", "."])
+            .env("RUST_LOG", "debug")
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        let mut stdin = child.stdin.take().unwrap();
+        let mut stdout = BufReader::new(child.stdout.take().unwrap()).lines();
+
+        let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" });
+        stdin.write_all(format!("{}\n", request).as_bytes()).await?;
+
+        // Only protocol frames may appear on stdout, even with debug logging on
+        let line = timeout(Duration::from_secs(5), stdout.next_line())
+            .await??
+            .ok_or("stdout closed")?;
+        let json: serde_json::Value = serde_json::from_str(&line)?;
+        assert_eq!(json["id"], 1);
+        assert_eq!(json["result"], json!({}));
+
+        // Closing stdin shuts the server down
+        drop(stdin);
+        let status = timeout(Duration::from_secs(5), child.wait()).await??;
+        assert!(status.success());
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_streamable_http_transport() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+        let endpoint = format!("http://127.0.0.1:{}/api/mcp", port);
+        let client = reqwest::Client::new();
+
+        // Initialize and pick up the session ID
+        let response = client
+            .post(&endpoint)
+            .header("Accept", "application/json")
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {},
+                    "clientInfo": { "name": "test-client", "version": "1.0.0" }
+                }
+            }))
+            .send()
+            .await?;
+
+        assert_eq!(response.status(), 200);
+        let session_id = response.headers()["mcp-session-id"].to_str()?.to_string();
+
+        // Requests without a session are rejected
+        let response = client
+            .post(&endpoint)
+            .json(&json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" }))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 400);
+
+        // Notifications are accepted without a body
+        let response = client
+            .post(&endpoint)
+            .header("Mcp-Session-Id", &session_id)
+            .json(&json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 202);
+
+        // Requests can be answered over SSE
+        let response = client
+            .post(&endpoint)
+            .header("Mcp-Session-Id", &session_id)
+            .header("Accept", "application/json, text/event-stream")
+            .json(&json!({ "jsonrpc": "2.0", "id": 3, "method": "component.list" }))
+            .send()
+            .await?;
+        assert_eq!(response.headers()["content-type"], "text/event-stream");
+
+        let body = response.text().await?;
+        let data = body
+            .lines()
+            .find_map(|line| line.strip_prefix("data:"))
+            .ok_or("no SSE data")?;
+        let json: serde_json::Value = serde_json::from_str(data.trim())?;
+        assert_eq!(json["id"], 3);
+        assert!(json["result"]["components"].is_array());
+
+        // Deleting the session ends it
+        let response = client
+            .delete(&endpoint)
+            .header("Mcp-Session-Id", &session_id)
+            .send()
+            .await?;
+        assert_eq!(response.status(), 204);
+
+        let response = client
+            .post(&endpoint)
+            .header("Mcp-Session-Id", &session_id)
+            .json(&json!({ "jsonrpc": "2.0", "id": 4, "method": "ping" }))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 404);
+
+        // Clean up
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_streamable_http_sessions() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server with short-lived sessions, two at most
+        let (port, handle) = start_test_server_with(ServerOptions {
+            http_session_timeout: Duration::from_millis(300),
+            max_http_sessions: 2,
+            ..ServerOptions::default()
+        })
+        .await;
+        let endpoint = format!("http://127.0.0.1:{}/api/mcp", port);
+        let status_url = format!("http://127.0.0.1:{}/api/status", port);
+        let client = reqwest::Client::new();
+        let initialize = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": {},
+                "clientInfo": { "name": "test-client", "version": "1.0.0" }
+            }
+        });
+
+        // A failed initialize leaves no session behind
+        let response = client
+            .post(&endpoint)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": "initialize" }))
+            .send()
+            .await?;
+        assert!(response.headers().get("mcp-session-id").is_none());
+        let json: serde_json::Value = response.json().await?;
+        assert_eq!(json["error"]["code"], -32602);
+
+        let status: serde_json::Value = client.get(&status_url).send().await?.json().await?;
+        assert_eq!(status["active_connections"], 0);
+
+        // Sessions past the limit are refused
+        let mut session_ids = Vec::new();
+        for _ in 0..2 {
+            let response = client.post(&endpoint).json(&initialize).send().await?;
+            assert_eq!(response.status(), 200);
+            session_ids.push(response.headers()["mcp-session-id"].to_str()?.to_string());
+        }
+
+        let response = client.post(&endpoint).json(&initialize).send().await?;
+        assert_eq!(response.status(), 503);
+
+        // A session with an open GET stream stays, an unused one expires
+        let stream = client
+            .get(&endpoint)
+            .header("Accept", "text/event-stream")
+            .header("Mcp-Session-Id", &session_ids[0])
+            .send()
+            .await?;
+        assert_eq!(stream.status(), 200);
+        tokio::time::sleep(Duration::from_millis(1000)).await;
+
+        for (session_id, expected) in session_ids.iter().zip([200, 404]) {
+            let response = client
+                .post(&endpoint)
+                .header("Mcp-Session-Id", session_id)
+                .json(&json!({ "jsonrpc": "2.0", "id": 2, "method": "ping" }))
+                .send()
+                .await?;
+            assert_eq!(response.status(), expected);
+        }
+
+        let status: serde_json::Value = client.get(&status_url).send().await?.json().await?;
+        assert_eq!(status["active_connections"], 1);
+
+        // The expired session made room for a new one
+        let response = client.post(&endpoint).json(&initialize).send().await?;
+        assert_eq!(response.status(), 200);
+
+        // Clean up
+        drop(stream);
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_client() -> Result<(), Box<dyn std::error::Error>> {
+        use orbit_mcp::api::build::{BuildMode, BuildOptions, BuildTarget};
+        use orbit_mcp::api::mcp::Implementation;
+        use orbit_mcp::client::{ClientError, McpClient};
+
+        // Start the server
+        let (port, handle) = start_test_server().await;
+        let options = BuildOptions {
+            target: BuildTarget::Web,
+            mode: BuildMode::Development,
+            optimize: false,
+            source_maps: false,
+            features: vec![],
+        };
+
+        // Typed calls over WebSocket
+        let client =
+            McpClient::connect_websocket(&format!("ws://127.0.0.1:{}/api/ws", port)).await?;
+        let components = client.list_components().await?;
+        let component = client.get_component("counter").await?;
+        assert_eq!(component.id, "counter");
+        assert!(components.iter().any(|c| c.id == component.id));
+
+        // Server errors keep their JSON-RPC code
+        match client
+            .request::<_, serde_json::Value>("component.frobnicate", json!({}))
+            .await
+        {
+            Err(ClientError::Rpc(error)) => assert_eq!(error.code, -32601),
+            other => panic!("Expected a server error, got {:?}", other),
+        }
+
+        // Events arrive on a stream while requests run concurrently
+        let mut events = Box::pin(client.events());
+        let (result, event) = tokio::join!(client.build(options.clone()), events.next());
+        assert!(result?.success);
+        assert_eq!(event.ok_or("no event")?.event_type, "build.progress");
+
+        // The same calls over Streamable HTTP, which needs `initialize` first
+        let client = McpClient::connect_http(&format!("http://127.0.0.1:{}/api/mcp", port))?;
+        let info = Implementation {
+            name: "test-client".to_string(),
+            version: "1.0.0".to_string(),
+        };
+        let result = client
+            .initialize(info.clone(), json!({ "experimental": { "events": {} } }))
+            .await?;
+        assert_eq!(result.server_info.name, "orbit-mcp");
+        assert_eq!(client.get_component("counter").await?.id, "counter");
+
+        // Calls made while `initialize` is under way wait for the session
+        let eager = McpClient::connect_http(&format!("http://127.0.0.1:{}/api/mcp", port))?;
+        let (result, component) = tokio::join!(
+            eager.initialize(info.clone(), json!({})),
+            eager.get_component("counter")
+        );
+        assert_eq!(result?.server_info.name, "orbit-mcp");
+        assert_eq!(component?.id, "counter");
+
+        // Dropping a client ends its session
+        let status_url = format!("http://127.0.0.1:{}/api/status", port);
+        let status: serde_json::Value = reqwest::get(&status_url).await?.json().await?;
+        let connected = status["active_connections"].as_u64().ok_or("no count")?;
+        drop(eager);
+        let mut ended = false;
+        for _ in 0..20 {
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            let status: serde_json::Value = reqwest::get(&status_url).await?.json().await?;
+            if status["active_connections"].as_u64() < Some(connected) {
+                ended = true;
+                break;
+            }
+        }
+        assert!(ended);
+
+        let mut events = Box::pin(client.events());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        let (result, event) = tokio::join!(client.build(options), async {
+            timeout(Duration::from_secs(2), events.next()).await
+        });
+        assert!(result?.success);
+        assert_eq!(event?.ok_or("no event")?.event_type, "build.progress");
+
+        // And over stdio, with the server as a child process
+        let mut command = tokio::process::Command::new(env!("CARGO_BIN_EXE_orbit-mcp"));
+        command.args(["--transport", "stdio", "--project", "."]);
+        let client = McpClient::connect_stdio(command)?;
+        client.initialize(info, json!({})).await?;
+        let tools = client.list_tools().await?;
+        assert!(tools.tools.iter().any(|tool| tool.name == "component_list"));
+
+        // Clean up
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_status_endpoint() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        // Call the status endpoint
+        let response = reqwest::get(format!("http://127.0.0.1:{}/api/status", port)).await?;
+
+        assert_eq!(response.status(), 200);
+
+        let json: serde_json::Value = response.json().await?;
+        assert_eq!(json["status"], "running");
+
+        // Clean up
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_openapi_document() -> Result<(), Box<dyn std::error::Error>> {
+        // Start the server
+        let (port, handle) = start_test_server().await;
+
+        // Fetch the OpenAPI document
+        let response = reqwest::get(format!("http://127.0.0.1:{}/api/openapi.json", port)).await?;
+
+        assert_eq!(response.status(), 200);
+
+        let json: serde_json::Value = response.json().await?;
+        assert_eq!(json["openapi"], "3.0.3");
+        assert!(json["components"]["schemas"]["Component"].is_object());
+        assert!(json["paths"]["/api/components/{id}"]["put"]["requestBody"].is_object());
+        assert!(json["x-jsonrpc-methods"]["docs.query"]["params"].is_object());
+
+        // The docs endpoint lists the same methods
+        let docs: serde_json::Value = reqwest::get(format!("http://127.0.0.1:{}/api/docs", port))
+            .await?
+            .json()
+            .await?;
+        assert!(docs["methods"]["component.analyze"].is_string());
+
+        // ...and links to the OpenAPI document
+        let link = docs["openapi"].as_str().ok_or("no OpenAPI link")?;
+        let response = reqwest::get(format!("http://127.0.0.1:{}{}", port, link)).await?;
+        assert_eq!(response.status(), 200);
+
+        // Clean up
+        handle.abort();
+
+        // Errors, such as a 401 for a missing token, have the documented shape
+        let (port, handle) = start_test_server_with(ServerOptions {
+            auth_token: Some("secret".to_string()),
+            ..ServerOptions::default()
+        })
+        .await;
+        let http = reqwest::Client::new();
+        let error_schema = json!({
+            "components": json["components"],
+            "allOf": [json["paths"]["/api/components"]["get"]["responses"]["default"]
+                ["content"]["application/json"]["schema"]],
+        });
+        let error_schema = jsonschema::JSONSchema::compile(&error_schema)
+            .map_err(|e| format!("invalid error schema: {}", e))?;
+
+        let response = http
+            .get(format!("http://127.0.0.1:{}/api/components", port))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 401);
+        let body: serde_json::Value = response.json().await?;
+        assert!(error_schema.is_valid(&body), "{}", body);
+
+        let response = http
+            .get(format!(
+                "http://127.0.0.1:{}/api/components/..%2F..%2Fsecrets",
+                port
+            ))
+            .bearer_auth("secret")
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+        let body: serde_json::Value = response.json().await?;
+        assert!(error_schema.is_valid(&body), "{}", body);
+        assert!(!error_schema.is_valid(&json!({ "message": "no error field" })));
+
+        // Clean up
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_authentication() -> Result<(), Box<dyn std::error::Error>> {
+        use orbit_mcp::api::mcp::Implementation;
+        use orbit_mcp::client::McpClient;
+        use orbit_mcp::utils::auth;
+        use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+        // The token file is readable by its owner only
+        let token = auth::generate_token();
+        let token_file = Path::new(env!("CARGO_TARGET_TMPDIR")).join("auth/mcp.token");
+        auth::write_token_file(&token_file, &token)?;
+        assert_eq!(auth::read_token_file(&token_file)?, token);
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&token_file)?.permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+
+        // Start the server
+        let (port, handle) = start_test_server_with(ServerOptions {
+            auth_token: Some(token.clone()),
+            ..ServerOptions::default()
+        })
+        .await;
+        let status_url = format!("http://127.0.0.1:{}/api/status", port);
+        let client = reqwest::Client::new();
+
+        // REST requests without a token get a 401 carrying a JSON-RPC error
+        let response = client.get(&status_url).send().await?;
+        assert_eq!(response.status(), 401);
+        assert!(response.headers()["www-authenticate"]
+            .to_str()?
+            .starts_with("Bearer"));
+        let json: serde_json::Value = response.json().await?;
+        assert_eq!(json["error"]["code"], -32003);
+        assert_eq!(json["id"], serde_json::Value::Null);
+
+        // So do requests with the wrong token, such as deletes
+        let response = client
+            .delete(format!("http://127.0.0.1:{}/api/components/counter", port))
+            .bearer_auth("not-the-token")
+            .send()
+            .await?;
+        assert_eq!(response.status(), 401);
+
+        // The right token gets through
+        let response = client.get(&status_url).bearer_auth(&token).send().await?;
+        assert_eq!(response.status(), 200);
+
+        // WebSocket upgrades without a token are refused
+        let url = format!("ws://127.0.0.1:{}/api/ws", port);
+        assert!(connect_async(url.as_str()).await.is_err());
+
+        // The token can be sent as a header...
+        let mut request = url.as_str().into_client_request()?;
+        request
+            .headers_mut()
+            .insert("Authorization", format!("Bearer {}", token).parse()?);
+        let (mut ws_stream, _) = connect_async(request).await?;
+        assert_eq!(next_json(&mut ws_stream).await?["method"], "server.welcome");
+        ws_stream.close(None).await?;
+
+        // ...or as a query parameter, for browsers
+        let (mut ws_stream, _) = connect_async(format!("{}?access_token={}", url, token)).await?;
+        assert_eq!(next_json(&mut ws_stream).await?["method"], "server.welcome");
+        ws_stream.close(None).await?;
+
+        // The client presents the token on every transport
+        let client = McpClient::connect_websocket_with_token(&url, &token).await?;
+        assert!(client.list_components().await.is_ok());
+        let endpoint = format!("http://127.0.0.1:{}/api/mcp", port);
+        let client = McpClient::connect_http_with_token(&endpoint, &token)?;
+        let info = Implementation {
+            name: "test-client".to_string(),
+            version: "1.0.0".to_string(),
+        };
+        client.initialize(info, json!({})).await?;
+        assert!(client.list_components().await.is_ok());
+
+        // Clean up
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_permission_scopes() -> Result<(), Box<dyn std::error::Error>> {
+        use orbit_mcp::api::auth::Scopes;
+        use orbit_mcp::api::build::{BuildMode, BuildOptions, BuildRequest, BuildTarget};
+        use orbit_mcp::client::{ClientError, McpClient};
+
+        // Whether a call was refused for lack of scope
+        fn forbidden<T>(result: Result<T, ClientError>) -> bool {
+            matches!(result, Err(ClientError::Rpc(error)) if error.code == -32004)
+        }
+
+        // Start the server with an admin token, a read-only token and a
+        // client limited to reading by name
+        let (port, handle) = start_test_server_with(ServerOptions {
+            auth_token: Some("admin-token".to_string()),
+            scoped_tokens: [("reader-token".to_string(), "read".parse::<Scopes>()?)].into(),
+            client_scopes: [("restricted-agent".to_string(), "read".parse::<Scopes>()?)].into(),
+            ..ServerOptions::default()
+        })
+        .await;
+        let url = format!("ws://127.0.0.1:{}/api/ws", port);
+        let components_url = format!("http://127.0.0.1:{}/api/components", port);
+        let http = reqwest::Client::new();
+
+        // The read-only token can read but not delete or build over REST
+        let response = http
+            .get(&components_url)
+            .bearer_auth("reader-token")
+            .send()
+            .await?;
+        assert_eq!(response.status(), 200);
+
+        let response = http
+            .delete(format!("{}/counter", components_url))
+            .bearer_auth("reader-token")
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+
+        let response = http
+            .post(format!("http://127.0.0.1:{}/api/project/build", port))
+            .bearer_auth("reader-token")
+            .json(&BuildRequest {
+                options: BuildOptions {
+                    target: BuildTarget::Web,
+                    mode: BuildMode::Development,
+                    optimize: false,
+                    source_maps: false,
+                    features: vec![],
+                },
+            })
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+
+        // ...nor over JSON-RPC, directly or through a tool
+        let reader = McpClient::connect_websocket_with_token(&url, "reader-token").await?;
+        assert!(reader.list_components().await.is_ok());
+        assert!(forbidden(reader.delete_component("counter").await));
+        // The scope is checked before the project is looked up
+        assert!(forbidden(
+            reader
+                .request::<_, serde_json::Value>(
+                    "component.create",
+                    json!({ "name": "Nowhere", "project": "/nowhere" }),
+                )
+                .await
+        ));
+        let result = reader
+            .call_tool("component_delete", json!({ "id": "counter" }))
+            .await?;
+        assert!(result.is_error);
+
+        // The admin token can do everything
+        let response = http
+            .delete(format!("{}/counter", components_url))
+            .bearer_auth("admin-token")
+            .send()
+            .await?;
+        assert_eq!(response.status(), 200);
+
+        let admin = McpClient::connect_websocket_with_token(&url, "admin-token").await?;
+        assert!(admin.delete_component("counter").await.is_ok());
+
+        // A session belongs to the token that started it: the read-only
+        // token can't use a session the admin token started
+        let mcp_url = format!("http://127.0.0.1:{}/api/mcp", port);
+        let response = http
+            .post(&mcp_url)
+            .bearer_auth("admin-token")
+            .json(&json!({
+                "jsonrpc": "2.0",
+                "id": 1,
+                "method": "initialize",
+                "params": {
+                    "protocolVersion": "2025-06-18",
+                    "capabilities": {},
+                    "clientInfo": { "name": "writer", "version": "1.0.0" }
+                }
+            }))
+            .send()
+            .await?;
+        let session_id = response.headers()["mcp-session-id"].to_str()?.to_string();
+
+        let create = json!({
+            "jsonrpc": "2.0",
+            "id": 2,
+            "method": "component.create",
+            "params": { "name": "Stolen" }
+        });
+        let response = http
+            .post(&mcp_url)
+            .bearer_auth("reader-token")
+            .header("mcp-session-id", &session_id)
+            .json(&create)
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+        let response = http
+            .post(&mcp_url)
+            .bearer_auth("reader-token")
+            .header("mcp-session-id", &session_id)
+            .json(&json!({ "jsonrpc": "2.0", "id": 3, "method": "ping" }))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+
+        // ...and can't learn its ID from the status either
+        let status: serde_json::Value = http
+            .get(format!("http://127.0.0.1:{}/api/status", port))
+            .bearer_auth("reader-token")
+            .send()
+            .await?
+            .json()
+            .await?;
+        let clients = status["clients"].as_array().ok_or("no clients")?;
+        assert!(!clients.is_empty());
+        assert!(clients.iter().all(|client| client.get("id").is_none()));
+        assert!(!status.to_string().contains(&session_id));
+
+        // Nor listen to the session or end it
+        let response = http
+            .get(&mcp_url)
+            .bearer_auth("reader-token")
+            .header("mcp-session-id", &session_id)
+            .header("accept", "text/event-stream")
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+        let response = http
+            .delete(&mcp_url)
+            .bearer_auth("reader-token")
+            .header("mcp-session-id", &session_id)
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+
+        // The token that started the session still can
+        let json: serde_json::Value = http
+            .post(&mcp_url)
+            .bearer_auth("admin-token")
+            .header("mcp-session-id", &session_id)
+            .json(&create)
+            .send()
+            .await?
+            .json()
+            .await?;
+        assert_eq!(json["result"]["component"]["name"], "Stolen");
+        let response = http
+            .delete(&mcp_url)
+            .bearer_auth("admin-token")
+            .header("mcp-session-id", &session_id)
+            .send()
+            .await?;
+        assert_eq!(response.status(), 204);
+
+        // Unless it identifies as a client limited by name
+        admin
+            .request::<_, serde_json::Value>("client.hello", json!({ "name": "restricted-agent" }))
+            .await?;
+        assert!(admin.list_components().await.is_ok());
+        assert!(forbidden(admin.delete_component("counter").await));
+
+        let status: serde_json::Value = http
+            .get(format!("http://127.0.0.1:{}/api/status", port))
+            .bearer_auth("admin-token")
+            .send()
+            .await?
+            .json()
+            .await?;
+        let restricted = status["clients"]
+            .as_array()
+            .ok_or("no clients")?
+            .iter()
+            .find(|client| client["name"] == "restricted-agent")
+            .ok_or("no restricted client")?;
+        assert_eq!(restricted["scopes"], json!(["read"]));
+        assert!(restricted["id"].is_string());
+
+        // Clean up
+        drop(reader);
+        drop(admin);
+        handle.abort();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        // In read-only mode nobody can change anything
+        let (port, handle) = start_test_server_with(ServerOptions {
+            read_only: true,
+            ..ServerOptions::default()
+        })
+        .await;
+
+        let client =
+            McpClient::connect_websocket(&format!("ws://127.0.0.1:{}/api/ws", port)).await?;
+        assert!(client.list_components().await.is_ok());
+        assert!(forbidden(client.delete_component("counter").await));
+        assert!(forbidden(
+            client
+                .request::<_, serde_json::Value>("test.run", json!({}))
+                .await
+        ));
+
+        let response = reqwest::Client::new()
+            .delete(format!("http://127.0.0.1:{}/api/components/counter", port))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+
+        // Clean up
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_path_sandboxing() -> Result<(), Box<dyn std::error::Error>> {
+        // A scratch project with secrets, and a file outside it
+        let scratch = Path::new(env!("CARGO_TARGET_TMPDIR")).join("sandbox");
+        let project = scratch.join("project");
+        tokio::fs::create_dir_all(project.join("src")).await?;
+        tokio::fs::create_dir_all(project.join(".git")).await?;
+        tokio::fs::create_dir_all(project.join("docs/private")).await?;
+        tokio::fs::write(project.join("src/counter.orbit"), "<template />\n").await?;
+        tokio::fs::write(project.join("docs/guide.md"), "# Guide\n").await?;
+        tokio::fs::write(project.join("docs/private/plans.md"), "# Plans\n").await?;
+        tokio::fs::write(project.join(".git/config"), "[core]\n").await?;
+        tokio::fs::write(project.join(".env"), "SECRET=1\n").await?;
+        tokio::fs::write(scratch.join("outside.orbit"), "<template />\n").await?;
+
+        // Symlinks that lead out of the project
+        #[cfg(unix)]
+        {
+            for link in ["src/leak.orbit", "src/elsewhere"] {
+                let _ = std::fs::remove_file(project.join(link));
+            }
+            std::os::unix::fs::symlink(
+                scratch.join("outside.orbit"),
+                project.join("src/leak.orbit"),
+            )?;
+            std::os::unix::fs::symlink(&scratch, project.join("src/elsewhere"))?;
+        }
+
+        // Start the server, denying one more directory
+        let mut denied_paths = ServerOptions::default().denied_paths;
+        denied_paths.push("docs/private".to_string());
+        let (port, handle) = start_test_server_in(
+            &project,
+            ServerOptions {
+                denied_paths,
+                ..ServerOptions::default()
+            },
+        )
+        .await;
+        let components_url = format!("http://127.0.0.1:{}/api/components", port);
+        let http = reqwest::Client::new();
+
+        // Component paths can't climb out of the project or be absolute elsewhere
+        for path in [
+            "../../evil.orbit",
+            "/tmp/evil.orbit",
+            ".git/hooks/evil.orbit",
+        ] {
+            let response = http
+                .post(&components_url)
+                .json(&json!({ "name": "Evil", "path": path }))
+                .send()
+                .await?;
+            assert_eq!(response.status(), 403, "{}", path);
+            let json: serde_json::Value = response.json().await?;
+            assert!(json["error"]
+                .as_str()
+                .unwrap_or_default()
+                .starts_with("Path not allowed"));
+        }
+
+        // Another server in the process keeps its own deny-list
+        let (other_port, other_handle) = start_test_server_in(
+            &project,
+            ServerOptions {
+                denied_paths: Vec::new(),
+                ..ServerOptions::default()
+            },
+        )
+        .await;
+        let response = http
+            .post(format!("http://127.0.0.1:{}/api/components", other_port))
+            .json(&json!({ "name": "Hook", "path": ".git/hooks/hook.orbit" }))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 201);
+        other_handle.abort();
+        let response = http
+            .post(&components_url)
+            .json(&json!({ "name": "Hook", "path": ".git/hooks/hook.orbit" }))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+
+        // Paths inside the project are fine
+        let response = http
+            .post(&components_url)
+            .json(&json!({ "name": "Fine", "path": "src/widgets/fine.orbit" }))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 201);
+
+        // Neither can component IDs
+        let response = http
+            .get(format!("{}/..%2F..%2Fsecrets", components_url))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+
+        // Resources on the deny-list or behind symlinks can't be read
+        let url = Url::parse(&format!("ws://127.0.0.1:{}/api/ws", port))?;
+        let (mut ws_stream, _) = connect_async(url).await?;
+        next_json(&mut ws_stream).await?;
+
+        let mut uris = vec![
+            "orbit://file/.env",
+            "orbit://file/.git/config",
+            "orbit://file/docs/private/plans.md",
+        ];
+        if cfg!(unix) {
+            uris.extend([
+                "orbit://component/leak",
+                "orbit://file/src/leak.orbit",
+                "orbit://file/src/elsewhere/outside.orbit",
+            ]);
+        }
+        for (id, uri) in uris.into_iter().enumerate() {
+            let request = json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "method": "resources/read",
+                "params": { "uri": uri }
+            });
+            ws_stream.send(Message::Text(request.to_string())).await?;
+
+            let json = next_json(&mut ws_stream).await?;
+            assert_eq!(json["error"]["code"], -32602, "{}", uri);
+            assert!(json["error"]["message"]
+                .as_str()
+                .unwrap_or_default()
+                .starts_with("Path not allowed"));
+        }
+
+        // ...nor are they listed
+        let request = json!({ "jsonrpc": "2.0", "id": "list", "method": "resources/list" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        let uris: Vec<&str> = json["result"]["resources"]
+            .as_array()
+            .ok_or("no resources")?
+            .iter()
+            .filter_map(|resource| resource["uri"].as_str())
+            .collect();
+        assert!(uris.contains(&"orbit://file/docs/guide.md"));
+        assert!(uris.contains(&"orbit://component/counter"));
+        assert!(!uris.contains(&"orbit://file/docs/private/plans.md"));
+        assert!(!uris.contains(&"orbit://component/leak"));
+
+        // Files inside the project can still be read
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": "counter",
+            "method": "resources/read",
+            "params": { "uri": "orbit://component/counter" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["result"]["contents"][0]["text"], "<template />\n");
+
+        // A root on the deny-list is no project of its own
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": "init",
+            "method": "initialize",
+            "params": {
+                "protocolVersion": "2025-06-18",
+                "capabilities": { "roots": {} },
+                "clientInfo": { "name": "editor", "version": "1.0.0" }
+            }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        next_json(&mut ws_stream).await?;
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        ws_stream
+            .send(Message::Text(notification.to_string()))
+            .await?;
+
+        let request = next_json(&mut ws_stream).await?;
+        assert_eq!(request["method"], "roots/list");
+        let git_dir = project.join(".git").canonicalize()?;
+        let response = json!({
+            "jsonrpc": "2.0",
+            "id": request["id"],
+            "result": {
+                "roots": [{ "uri": format!("file://{}", git_dir.display()), "name": "git" }]
+            }
+        });
+        ws_stream.send(Message::Text(response.to_string())).await?;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+
+        let request = json!({
+            "jsonrpc": "2.0",
+            "id": "config",
+            "method": "resources/read",
+            "params": { "uri": "orbit://file/config", "project": "git" }
+        });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert!(json["error"].is_object());
+        assert!(json["result"].is_null());
+
+        let request = json!({ "jsonrpc": "2.0", "id": "projects", "method": "project.list" });
+        ws_stream.send(Message::Text(request.to_string())).await?;
+        let json = next_json(&mut ws_stream).await?;
+        assert_eq!(json["result"]["projects"].as_array().map(Vec::len), Some(1));
+        assert_ne!(json["result"]["projects"][0]["name"], "git");
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_origin_checks() -> Result<(), Box<dyn std::error::Error>> {
+        use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+
+        // Start the server
+        let (port, handle) = start_test_server_with(ServerOptions {
+            allowed_origins: vec!["http://localhost:5173".to_string()],
+            ..ServerOptions::default()
+        })
+        .await;
+        let status_url = format!("http://127.0.0.1:{}/api/status", port);
+        let client = reqwest::Client::new();
+
+        // Clients that aren't browsers send no Origin and get through
+        let response = client.get(&status_url).send().await?;
+        assert_eq!(response.status(), 200);
+
+        // Pages on other sites are refused, and get no CORS headers
+        let response = client
+            .get(&status_url)
+            .header("Origin", "http://evil.example")
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+        assert!(response
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none());
+        let response = client
+            .request(reqwest::Method::OPTIONS, &status_url)
+            .header("Origin", "http://evil.example")
+            .header("Access-Control-Request-Method", "DELETE")
+            .send()
+            .await?;
+        assert!(response
+            .headers()
+            .get("access-control-allow-origin")
+            .is_none());
+
+        // Allowed origins pass preflight and can read responses
+        let response = client
+            .request(reqwest::Method::OPTIONS, &status_url)
+            .header("Origin", "http://localhost:5173")
+            .header("Access-Control-Request-Method", "POST")
+            .header("Access-Control-Request-Headers", "content-type")
+            .send()
+            .await?;
+        assert_eq!(
+            response.headers()["access-control-allow-origin"],
+            "http://localhost:5173"
+        );
+        let response = client
+            .get(&status_url)
+            .header("Origin", "http://localhost:5173")
+            .send()
+            .await?;
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers()["access-control-allow-origin"],
+            "http://localhost:5173"
+        );
+
+        // Host names rebound to the server are refused
+        let response = client
+            .get(&status_url)
+            .header("Host", format!("attacker.example:{}", port))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 403);
+        let response = client
+            .get(&status_url)
+            .header("Host", format!("localhost:{}", port))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 200);
+
+        // WebSocket upgrades check the Origin too
+        let url = format!("ws://127.0.0.1:{}/api/ws", port);
+        let mut request = url.as_str().into_client_request()?;
+        request
+            .headers_mut()
+            .insert("Origin", "http://evil.example".parse()?);
+        assert!(connect_async(request).await.is_err());
+
+        let mut request = url.as_str().into_client_request()?;
+        request
+            .headers_mut()
+            .insert("Origin", "http://localhost:5173".parse()?);
+        let (mut ws_stream, _) = connect_async(request).await?;
+        assert_eq!(next_json(&mut ws_stream).await?["method"], "server.welcome");
+
+        // Clean up
+        ws_stream.close(None).await?;
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_client_tls() -> Result<(), Box<dyn std::error::Error>> {
+        use orbit_mcp::api::mcp::Implementation;
+        use orbit_mcp::client::{ConnectOptions, McpClient};
+        use orbit_mcp::utils::tls::TlsConfig;
+
+        // Start the server with a self-signed certificate
+        let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("client-tls");
+        let _ = std::fs::remove_dir_all(&dir);
+        let config = TlsConfig::self_signed(&dir, &["127.0.0.1".to_string()])?;
+        let fingerprint = config.fingerprint()?;
+        let (port, handle) = start_test_server_with(ServerOptions {
+            tls: Some(config),
+            ..ServerOptions::default()
+        })
+        .await;
+        let endpoint = format!("https://127.0.0.1:{}/api/mcp", port);
+        let info = Implementation {
+            name: "test-client".to_string(),
+            version: "1.0.0".to_string(),
+        };
+
+        // Pinning the certificate lets the client trust it over HTTPS
+        let pinned = ConnectOptions::default().with_fingerprint(fingerprint.to_lowercase());
+        let client = McpClient::connect_http_with(&endpoint, &pinned)?;
+        client.initialize(info.clone(), json!({})).await?;
+        assert_eq!(client.get_component("counter").await?.id, "counter");
+
+        // Another certificate, or none pinned, is refused
+        let wrong = ConnectOptions::default().with_fingerprint("00".repeat(32));
+        let client = McpClient::connect_http_with(&endpoint, &wrong)?;
+        assert!(client.initialize(info.clone(), json!({})).await.is_err());
+
+        let client = McpClient::connect_http(&endpoint)?;
+        assert!(client.initialize(info, json!({})).await.is_err());
+
+        // A pin needs a TLS URL
+        let plain = format!("http://127.0.0.1:{}/api/mcp", port);
+        assert!(McpClient::connect_http_with(&plain, &pinned).is_err());
+
+        // Clean up
+        handle.abort();
+
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_tls() -> Result<(), Box<dyn std::error::Error>> {
+        use orbit_mcp::client::{ConnectOptions, McpClient};
+        use orbit_mcp::utils::tls::{self, TlsConfig};
+        use sha2::{Digest, Sha256};
+
+        // A self-signed certificate is generated once, then reused
+        let dir = Path::new(env!("CARGO_TARGET_TMPDIR")).join("tls");
+        let _ = std::fs::remove_dir_all(&dir);
+        let mut names = vec!["localhost".to_string(), "127.0.0.1".to_string()];
+        let first = TlsConfig::self_signed(&dir, &names)?.fingerprint()?;
+        assert_eq!(first.len(), 32 * 3 - 1);
+        names.reverse();
+        assert_eq!(TlsConfig::self_signed(&dir, &names)?.fingerprint()?, first);
+
+        // ...until it's asked to cover other names
+        names.push("mcp.example.test".to_string());
+        let config = TlsConfig::self_signed(&dir, &names)?;
+        let fingerprint = config.fingerprint()?;
+        assert_ne!(fingerprint, first);
+        assert_eq!(
+            TlsConfig::self_signed(&dir, &names)?.fingerprint()?,
+            fingerprint
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&config.key_path)?.permissions().mode();
+            assert_eq!(mode & 0o777, 0o600);
+        }
+        assert!(tls::fingerprint(b"not a certificate").is_err());
+
+        // Start the server
+        let (port, handle) = start_test_server_with(ServerOptions {
+            tls: Some(config),
+            ..ServerOptions::default()
+        })
+        .await;
+        let client = reqwest::Client::builder()
+            .danger_accept_invalid_certs(true)
+            .tls_info(true)
+            .build()?;
+
+        // The server speaks HTTPS and reports the fingerprint clients can pin
+        let response = client
+            .get(format!("https://127.0.0.1:{}/api/status", port))
+            .send()
+            .await?;
+        assert_eq!(response.status(), 200);
+        let presented = response
+            .extensions()
+            .get::<reqwest::tls::TlsInfo>()
+            .and_then(|info| info.peer_certificate())
+            .map(|der| {
+                Sha256::digest(der)
+                    .iter()
+                    .map(|byte| format!("{:02X}", byte))
+                    .collect::<Vec<_>>()
+                    .join(":")
+            });
+        assert_eq!(presented.as_deref(), Some(fingerprint.as_str()));
+        let json: serde_json::Value = response.json().await?;
+        assert_eq!(json["tls_fingerprint"], fingerprint);
+
+        // A client pinning the reported fingerprint connects over WSS
+        let pinned = ConnectOptions::default()
+            .with_fingerprint(json["tls_fingerprint"].as_str().ok_or("no fingerprint")?);
+        let url = format!("wss://127.0.0.1:{}/api/ws", port);
+        let client = McpClient::connect_websocket_with(&url, &pinned).await?;
+        assert_eq!(client.get_component("counter").await?.id, "counter");
+
+        let wrong = ConnectOptions::default().with_fingerprint(first);
+        assert!(McpClient::connect_websocket_with(&url, &wrong)
+            .await
+            .is_err());
+
+        // Plain HTTP is no longer served
+        assert!(
+            reqwest::get(format!("http://127.0.0.1:{}/api/status", port))
+                .await
+                .is_err()
+        );
 
         // Clean up
         handle.abort();